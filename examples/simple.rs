@@ -3,11 +3,12 @@ extern crate glium;
 
 use glium::DisplayBuild;
 use gg::event::step::fixed_60;
+use gg::clock::MonotonicClock;
 
 fn main() {
     let display = glium::glutin::WindowBuilder::new().build_glium().unwrap();
 
-    fixed_60(|_dt| {
+    fixed_60(&MonotonicClock, |_dt| {
         use gg::event::StepResult;
         for ev in display.poll_events() {
             match ev {
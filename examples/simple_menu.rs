@@ -1,8 +1,8 @@
 extern crate gg;
 extern crate glium;
 
-use glium::DisplayBuild;
-use gg::scene::{Scene, StackSceneManager};
+use gg::scene::Scene;
+use gg::event::{Binding, KeyCode};
 use gg::traits::HasId;
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -29,15 +29,14 @@ impl Scene for MainMenu {
     fn leave(&mut self, _state: &mut Self::State) {
         println!("Leave State");
     }
-    fn keypress(&mut self, _state: &mut Self::State, keys: &gg::event::Keys) {
-        use gg::event::KeyCode::*;
-
-        if keys.pressed(Escape) {
+    fn actions(&mut self, _state: &mut Self::State, actions: &gg::event::Actions) {
+        if actions.pressed("back") {
             self.quit();
         }
     }
 
-    fn display(&mut self, _state: &mut Self::State, display: &glium::backend::glutin_backend::GlutinFacade) {
+    fn display(&mut self, _state: &mut Self::State, display: &glium::backend::glutin_backend::GlutinFacade,
+               _alpha: f64) {
         use glium::Surface;
         let mut target = display.draw();
         target.clear_color(0., 0., 1., 1.);
@@ -61,18 +60,16 @@ impl HasId for MainMenu {
 }
 
 fn main() {
-    let display = glium::glutin::WindowBuilder::new().build_glium().unwrap();
-
     let state = Rc::new(RefCell::new(GameState));
 
-    let game = gg::Game::new(
-        state.clone(),
-        StackSceneManager::with_scene(
-            state.clone(),
-            Box::new(MainMenu { quit: false })
-        ),
-        display
-    );
+    let game = gg::GameBuilder::new(state)
+        .title("Simple Menu")
+        .size(800, 600)
+        .scene(Box::new(MainMenu { quit: false }))
+        .build()
+        .unwrap();
+
+    game.actions().bind("back", Binding::Key(KeyCode::Escape));
 
     // Internally calls the draw/tick loop
     game.kickoff();
@@ -0,0 +1,276 @@
+use std::error::Error;
+use std::fmt;
+use glium::{DisplayBuild, GliumCreationError};
+use glium::glutin::{self, WindowBuilder, CreationError};
+use scene::{Scene, StackSceneManager};
+use game::{Game, LoopMode, MAX_TICK_RATE};
+
+/// Everything that can go wrong while building a `Game`
+#[derive(Debug)]
+pub enum BuildError {
+    /// The window would have a width or height of 0
+    InvalidSize(u32, u32),
+    /// Multisampling has to be 0 (disabled) or a power of two up to 16
+    InvalidMultisampling(u16),
+    /// The game has to tick at least once and at most `MAX_TICK_RATE` times
+    /// per second
+    InvalidTickRate(u32),
+    /// There was no initial scene given
+    NoScene,
+    /// The window or its OpenGL context could not be created
+    Creation(GliumCreationError<CreationError>),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::InvalidSize(w, h) =>
+                write!(f, "Invalid window size {}x{}, both have to be at least 1", w, h),
+            BuildError::InvalidMultisampling(m) =>
+                write!(f, "Invalid multisampling of {}, has to be 0 or a power of two up to 16", m),
+            BuildError::InvalidTickRate(r) =>
+                write!(f, "Invalid tick rate of {} per second, has to be between 1 and {}",
+                       r, MAX_TICK_RATE),
+            BuildError::NoScene =>
+                write!(f, "No initial scene was given"),
+            BuildError::Creation(ref e) =>
+                write!(f, "Could not create the window: {}", e),
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn description(&self) -> &str {
+        match *self {
+            BuildError::InvalidSize(..) => "invalid window size",
+            BuildError::InvalidMultisampling(..) => "invalid multisampling",
+            BuildError::InvalidTickRate(..) => "invalid tick rate",
+            BuildError::NoScene => "no initial scene",
+            BuildError::Creation(ref e) => e.description(),
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            BuildError::Creation(ref e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<GliumCreationError<CreationError>> for BuildError {
+    fn from(err: GliumCreationError<CreationError>) -> BuildError {
+        BuildError::Creation(err)
+    }
+}
+
+/// Creates a `Game` together with its window, managing the scenes with a
+/// `StackSceneManager`.
+///
+/// ```ignore
+/// let game = GameBuilder::new(state)
+///     .title("My Game")
+///     .size(800, 600)
+///     .vsync(true)
+///     .scene(Box::new(MainMenu))
+///     .build()
+///     .unwrap();
+/// game.kickoff();
+/// ```
+pub struct GameBuilder<T: Sized + Clone> {
+    state: T,
+    title: String,
+    size: (u32, u32),
+    resizable: bool,
+    fullscreen: bool,
+    vsync: bool,
+    multisampling: u16,
+    loop_mode: LoopMode,
+    scene: Option<Box<Scene<State=T>>>,
+}
+
+impl<T: Clone> GameBuilder<T> {
+    /// Starts building a game with the given state. Per default this is a
+    /// resizable 1024x768 window without vsync and multisampling, ticking
+    /// 60 times per second.
+    pub fn new(state: T) -> GameBuilder<T> {
+        GameBuilder {
+            state: state,
+            title: "gg".to_string(),
+            size: (1024, 768),
+            resizable: true,
+            fullscreen: false,
+            vsync: false,
+            multisampling: 0,
+            loop_mode: LoopMode::Fixed(60),
+            scene: None,
+        }
+    }
+
+    /// The title of the window
+    pub fn title(mut self, title: &str) -> GameBuilder<T> {
+        self.title = title.to_string();
+        self
+    }
+
+    /// The size of the window in pixels
+    pub fn size(mut self, width: u32, height: u32) -> GameBuilder<T> {
+        self.size = (width, height);
+        self
+    }
+
+    /// Whether the user may resize the window
+    pub fn resizable(mut self, resizable: bool) -> GameBuilder<T> {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Whether the window should cover the primary monitor
+    pub fn fullscreen(mut self, fullscreen: bool) -> GameBuilder<T> {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Whether presenting a frame should wait for the vertical sync, this is
+    /// always the case with `LoopMode::VSync` and never with
+    /// `LoopMode::Uncapped`
+    pub fn vsync(mut self, vsync: bool) -> GameBuilder<T> {
+        self.vsync = vsync;
+        self
+    }
+
+    /// The amount of samples per pixel, 0 disables multisampling
+    pub fn multisampling(mut self, samples: u16) -> GameBuilder<T> {
+        self.multisampling = samples;
+        self
+    }
+
+    /// How many times per second the scenes get updated, see
+    /// `LoopMode::Fixed`
+    pub fn tick_rate(mut self, rate: u32) -> GameBuilder<T> {
+        self.loop_mode = LoopMode::Fixed(rate);
+        self
+    }
+
+    /// How the game loop is paced, see `LoopMode`
+    pub fn loop_mode(mut self, mode: LoopMode) -> GameBuilder<T> {
+        self.loop_mode = mode;
+        self
+    }
+
+    /// The scene the game starts with
+    pub fn scene(mut self, scene: Box<Scene<State=T>>) -> GameBuilder<T> {
+        self.scene = Some(scene);
+        self
+    }
+
+    /// Checks the settings and creates the window as well as the game
+    pub fn build(self) -> Result<Game<T, StackSceneManager<T>>, BuildError> {
+        let (width, height) = self.size;
+        if width == 0 || height == 0 {
+            return Err(BuildError::InvalidSize(width, height));
+        }
+        if self.multisampling > 16 || self.multisampling & self.multisampling.wrapping_sub(1) != 0 {
+            return Err(BuildError::InvalidMultisampling(self.multisampling));
+        }
+        if let LoopMode::Fixed(rate) = self.loop_mode {
+            if rate == 0 || rate > MAX_TICK_RATE {
+                return Err(BuildError::InvalidTickRate(rate));
+            }
+        }
+        let scene = match self.scene {
+            Some(scene) => scene,
+            None => return Err(BuildError::NoScene)
+        };
+
+        let mut window = WindowBuilder::new()
+            .with_title(self.title)
+            .with_dimensions(width, height);
+        if !self.resizable {
+            window = window
+                .with_min_dimensions(width, height)
+                .with_max_dimensions(width, height);
+        }
+        if self.fullscreen {
+            window = window.with_fullscreen(glutin::get_primary_monitor());
+        }
+        let vsync = match self.loop_mode {
+            LoopMode::VSync => true,
+            LoopMode::Uncapped => false,
+            LoopMode::Fixed(_) | LoopMode::EventDriven => self.vsync
+        };
+        if vsync {
+            window = window.with_vsync();
+        }
+        if self.multisampling > 0 {
+            window = window.with_multisampling(self.multisampling);
+        }
+
+        let display = try!(window.build_glium());
+
+        let mut game = Game::new(
+            self.state.clone(),
+            StackSceneManager::with_scene(self.state, scene),
+            display
+        );
+        game.set_loop_mode(self.loop_mode);
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use game::{LoopMode, MAX_TICK_RATE};
+    use scene::Scene;
+    use traits::HasId;
+
+    struct TestScene;
+
+    impl HasId for TestScene {
+        fn get_id(&self) -> usize {
+            0
+        }
+    }
+
+    impl Scene for TestScene {
+        type State = ();
+    }
+
+    fn error<T>(result: Result<T, BuildError>) -> BuildError {
+        match result {
+            Ok(_) => panic!("Expected the build to fail"),
+            Err(e) => e
+        }
+    }
+
+    #[test]
+    fn validates_settings() {
+        match error(GameBuilder::new(()).scene(Box::new(TestScene)).size(0, 10).build()) {
+            BuildError::InvalidSize(0, 10) => {},
+            e => panic!("Unexpected error {:?}", e)
+        }
+
+        match error(GameBuilder::new(()).scene(Box::new(TestScene)).multisampling(3).build()) {
+            BuildError::InvalidMultisampling(3) => {},
+            e => panic!("Unexpected error {:?}", e)
+        }
+
+        match error(GameBuilder::new(()).scene(Box::new(TestScene)).tick_rate(0).build()) {
+            BuildError::InvalidTickRate(0) => {},
+            e => panic!("Unexpected error {:?}", e)
+        }
+
+        let too_fast = GameBuilder::new(()).scene(Box::new(TestScene))
+            .loop_mode(LoopMode::Fixed(MAX_TICK_RATE + 1)).build();
+        match error(too_fast) {
+            BuildError::InvalidTickRate(r) if r == MAX_TICK_RATE + 1 => {},
+            e => panic!("Unexpected error {:?}", e)
+        }
+
+        match error(GameBuilder::new(()).build()) {
+            BuildError::NoScene => {},
+            e => panic!("Unexpected error {:?}", e)
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::thread;
+use time;
+
+/// A source of monotonic time. The game loop, `Game` and through it `Keys`
+/// only ever ask a `Clock` what time it is, which allows to swap in a
+/// `SimulatedClock` to test timing dependent behaviour.
+pub trait Clock {
+    /// The current time in nanoseconds, only the difference between two
+    /// calls is meaningful.
+    fn now_ns(&self) -> u64;
+
+    /// Waits for (at least) `ns` nanoseconds.
+    fn sleep_ns(&self, ns: u64);
+
+    /// The current time in seconds
+    fn now(&self) -> f64 {
+        self.now_ns() as f64 / 1000_000_000. as f64
+    }
+}
+
+/// The real clock, backed by the high precision timer of the OS.
+#[derive(Copy, Clone, Debug)]
+pub struct MonotonicClock;
+
+impl Clock for MonotonicClock {
+    fn now_ns(&self) -> u64 {
+        time::precise_time_ns()
+    }
+
+    fn sleep_ns(&self, ns: u64) {
+        thread::sleep_ms((ns / 1000_000) as u32);
+    }
+}
+
+/// A clock that only moves forward when told to. Sleeping advances it
+/// instantly, so loops driven by it run at full speed while still seeing
+/// exact time steps.
+///
+/// Clones share the same time, so you can hand one to the `Game` and keep one
+/// around to advance or inspect it.
+#[derive(Clone, Debug)]
+pub struct SimulatedClock {
+    now: Rc<Cell<u64>>
+}
+
+impl SimulatedClock {
+    /// Creates a new clock starting at 0
+    pub fn new() -> SimulatedClock {
+        SimulatedClock::starting_at(0)
+    }
+
+    /// Creates a new clock starting at the given nanoseconds
+    pub fn starting_at(ns: u64) -> SimulatedClock {
+        SimulatedClock {
+            now: Rc::new(Cell::new(ns))
+        }
+    }
+
+    /// Moves the clock forward by `ns` nanoseconds
+    pub fn advance(&self, ns: u64) {
+        self.now.set(self.now.get() + ns);
+    }
+
+    /// Moves the clock forward by `secs` seconds
+    pub fn advance_secs(&self, secs: f64) {
+        self.advance((secs * 1000_000_000. as f64) as u64);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now_ns(&self) -> u64 {
+        self.now.get()
+    }
+
+    fn sleep_ns(&self, ns: u64) {
+        self.advance(ns);
+    }
+}
+
+struct TimeControlData {
+    scale: Cell<f64>,
+    paused: Cell<bool>,
+    steps: Cell<u32>,
+}
+
+/// Controls how fast simulated time passes relative to real time, and allows
+/// to freeze it or advance it tick by tick.
+///
+/// This is a cheap handle, clones control the same `Game`. Get one with
+/// `Game::time_control` and put it into your state to drive it from your
+/// scenes.
+#[derive(Clone)]
+pub struct TimeControl {
+    data: Rc<TimeControlData>
+}
+
+impl TimeControl {
+    /// Creates a new running time control at normal speed
+    pub fn new() -> TimeControl {
+        TimeControl {
+            data: Rc::new(TimeControlData {
+                scale: Cell::new(1.0),
+                paused: Cell::new(false),
+                steps: Cell::new(0),
+            })
+        }
+    }
+
+    /// The factor every `dt` is multiplied with
+    pub fn scale(&self) -> f64 {
+        self.data.scale.get()
+    }
+
+    /// Sets the factor every `dt` is multiplied with, `0.25` is a quarter of
+    /// the normal speed.
+    ///
+    /// **This panics if the scale is negative!**
+    pub fn set_scale(&self, scale: f64) {
+        if scale < 0.0 {
+            panic!("Tried to set a negative time scale of {}.", scale);
+        }
+        self.data.scale.set(scale);
+    }
+
+    /// Whether the simulation is currently frozen
+    pub fn paused(&self) -> bool {
+        self.data.paused.get()
+    }
+
+    /// Freezes the simulation, scenes will still be drawn
+    pub fn pause(&self) {
+        self.data.paused.set(true);
+    }
+
+    /// Unfreezes the simulation
+    pub fn resume(&self) {
+        self.data.paused.set(false);
+        self.data.steps.set(0);
+    }
+
+    /// Pauses if running and resumes if paused
+    pub fn toggle_pause(&self) {
+        if self.paused() {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Advances a paused simulation by exactly one tick
+    pub fn step(&self) {
+        if self.paused() {
+            self.data.steps.set(self.data.steps.get() + 1);
+        }
+    }
+
+    /// Gives the `dt` that should be simulated for a tick of `dt` real time,
+    /// or `None` if the tick should be skipped because we are paused.
+    pub fn apply(&self, dt: f64) -> Option<f64> {
+        if self.paused() {
+            let steps = self.data.steps.get();
+            if steps == 0 {
+                return None;
+            }
+            self.data.steps.set(steps - 1);
+        }
+        Some(dt * self.scale())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_control_steps() {
+        let control = TimeControl::new();
+        control.set_scale(0.25);
+
+        assert_eq!(control.apply(1.0), Some(0.25));
+
+        control.pause();
+        assert_eq!(control.apply(1.0), None);
+
+        control.step();
+        control.clone().step();
+        assert_eq!(control.apply(1.0), Some(0.25));
+        assert_eq!(control.apply(1.0), Some(0.25));
+        assert_eq!(control.apply(1.0), None);
+
+        control.toggle_pause();
+        assert_eq!(control.apply(2.0), Some(0.5));
+    }
+
+    #[test]
+    fn simulated_clock_shares_time() {
+        let clock = SimulatedClock::new();
+        let other = clock.clone();
+
+        clock.advance(500);
+        other.sleep_ns(500);
+
+        assert_eq!(clock.now_ns(), 1000);
+        assert_eq!(other.now_ns(), 1000);
+
+        clock.advance_secs(1.5);
+        assert_eq!(other.now(), 1.500001);
+    }
+}
@@ -0,0 +1,688 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use glium::glutin::ElementState;
+use super::{Keys, KeyCode, KeyState, Mouse, MouseButton, Gamepads, PhysicalKey};
+use super::{key_name, key_from_name};
+use super::gamepad::{Button, Axis};
+use super::axis::VirtualAxis;
+
+/// How far an axis has to be pushed to count as held
+pub const AXIS_THRESHOLD: f64 = 0.5;
+
+/// A single input an action can be bound to
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Binding {
+    /// A key on the keyboard
+    Key(KeyCode),
+    /// A key by its position on the keyboard, whatever the layout
+    Physical(PhysicalKey),
+    /// A mouse button
+    Mouse(MouseButton),
+    /// A button on any gamepad
+    Button(Button),
+    /// An axis on any gamepad, giving its full range
+    Axis(Axis),
+    /// Only the positive half of an axis on any gamepad
+    AxisPositive(Axis),
+    /// Only the negative half of an axis on any gamepad, flipped to be
+    /// positive
+    AxisNegative(Axis),
+    /// Two keys forming an axis, the first one giving -1 and the second one
+    /// giving 1
+    KeyAxis(KeyCode, KeyCode),
+}
+
+impl Binding {
+    /// Whether the binding counts as held and its value
+    fn evaluate(&self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads) -> (bool, f64) {
+        let digital = |held: bool| (held, if held { 1.0 } else { 0.0 });
+        let axis = |axis: Axis| {
+            gamepads.all().iter()
+                .map(|pad| pad.axis(axis))
+                .fold(0.0, |strongest: f64, v| if v.abs() > strongest.abs() { v } else { strongest })
+        };
+        let analog = |value: f64| (value.abs() >= AXIS_THRESHOLD, value);
+
+        match *self {
+            Binding::Key(key) => digital(keys.held(key)),
+            Binding::Physical(key) => {
+                digital(keys.scancodes().held(key.scancode()))
+            },
+            Binding::Mouse(button) => digital(mouse.held(button)),
+            Binding::Button(button) => {
+                digital(gamepads.all().iter().any(|pad| pad.held(button)))
+            },
+            Binding::Axis(a) => analog(axis(a)),
+            Binding::AxisPositive(a) => analog(axis(a).max(0.0)),
+            Binding::AxisNegative(a) => analog((-axis(a)).max(0.0)),
+            Binding::KeyAxis(negative, positive) => {
+                let mut value = 0.0;
+                if keys.held(negative) {
+                    value -= 1.0;
+                }
+                if keys.held(positive) {
+                    value += 1.0;
+                }
+                (value != 0.0, value)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    /// Writes the binding the way it appears in config files
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = |key: KeyCode| key_name(key).expect("Every key has a name");
+        match *self {
+            Binding::Key(k) => write!(f, "key:{}", key(k)),
+            Binding::Physical(k) => write!(f, "physical:{}", k.name()),
+            Binding::Mouse(MouseButton::Left) => write!(f, "mouse:Left"),
+            Binding::Mouse(MouseButton::Right) => write!(f, "mouse:Right"),
+            Binding::Mouse(MouseButton::Middle) => write!(f, "mouse:Middle"),
+            Binding::Mouse(MouseButton::Other(n)) => write!(f, "mouse:{}", n),
+            Binding::Button(b) => write!(f, "button:{}", b.name()),
+            Binding::Axis(a) => write!(f, "axis:{}", a.name()),
+            Binding::AxisPositive(a) => write!(f, "axis+:{}", a.name()),
+            Binding::AxisNegative(a) => write!(f, "axis-:{}", a.name()),
+            Binding::KeyAxis(n, p) => write!(f, "keys:{}:{}", key(n), key(p)),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    /// Reads a binding the way it appears in config files, like `key:Space`,
+    /// `physical:W`, `mouse:Left`, `button:South`, `axis:LeftX`,
+    /// `axis+:RightTrigger` or `keys:Left:Right`
+    fn from_str(s: &str) -> Result<Binding, String> {
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        let key = |name: &str| key_from_name(name)
+            .ok_or_else(|| format!("Unknown key '{}'", name));
+        let axis = |name: &str| Axis::from_name(name)
+            .ok_or_else(|| format!("Unknown axis '{}'", name));
+
+        match (parts[0], parts.len()) {
+            ("key", 2) => Ok(Binding::Key(try!(key(parts[1])))),
+            ("physical", 2) => PhysicalKey::from_name(parts[1]).map(Binding::Physical)
+                .ok_or_else(|| format!("Unknown physical key '{}'", parts[1])),
+            ("mouse", 2) => match parts[1] {
+                "Left" => Ok(Binding::Mouse(MouseButton::Left)),
+                "Right" => Ok(Binding::Mouse(MouseButton::Right)),
+                "Middle" => Ok(Binding::Mouse(MouseButton::Middle)),
+                other => other.parse().map(|n| Binding::Mouse(MouseButton::Other(n)))
+                    .map_err(|_| format!("Unknown mouse button '{}'", other))
+            },
+            ("button", 2) => Button::from_name(parts[1]).map(Binding::Button)
+                .ok_or_else(|| format!("Unknown button '{}'", parts[1])),
+            ("axis", 2) => Ok(Binding::Axis(try!(axis(parts[1])))),
+            ("axis+", 2) => Ok(Binding::AxisPositive(try!(axis(parts[1])))),
+            ("axis-", 2) => Ok(Binding::AxisNegative(try!(axis(parts[1])))),
+            ("keys", 3) => Ok(Binding::KeyAxis(try!(key(parts[1])), try!(key(parts[2])))),
+            _ => Err(format!("Invalid binding '{}'", s.trim()))
+        }
+    }
+}
+
+/// Everything that can go wrong while loading or saving bindings
+#[derive(Debug)]
+pub enum ActionError {
+    /// The file could not be read or written
+    Io(io::Error),
+    /// A line of the file could not be understood
+    Parse {
+        /// The line, counting from 1
+        line: usize,
+        /// What was wrong with it
+        message: String,
+    },
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ActionError::Io(ref e) => write!(f, "Could not access the bindings: {}", e),
+            ActionError::Parse { line, ref message } =>
+                write!(f, "Invalid bindings in line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ActionError {
+    fn description(&self) -> &str {
+        match *self {
+            ActionError::Io(ref e) => e.description(),
+            ActionError::Parse { .. } => "invalid bindings",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            ActionError::Io(ref e) => Some(e),
+            ActionError::Parse { .. } => None
+        }
+    }
+}
+
+impl From<io::Error> for ActionError {
+    fn from(err: io::Error) -> ActionError {
+        ActionError::Io(err)
+    }
+}
+
+/// Maps action names like "jump" or "move_x" to the inputs that trigger
+/// them.
+///
+/// This is a cheap handle, clones share the same bindings. Get one with
+/// `Game::actions` and put it into your state to change the bindings from
+/// within a scene, for example in a remapping screen.
+///
+/// Bindings can be saved to and loaded from a simple text format, with one
+/// action per line:
+///
+/// ```text
+/// # Comments start with a hash
+/// jump = key:Space, button:South
+/// move_x = keys:Left:Right, axis:LeftX
+/// ```
+///
+/// Named `VirtualAxis`es can be added too, the map only holds how they are
+/// set up while every `Actions` smooths its own copy of them every tick, read
+/// through `Actions::axis`. They are not part of the config format.
+#[derive(Clone)]
+pub struct ActionMap {
+    bindings: Rc<RefCell<HashMap<String, Vec<Binding>>>>,
+    axes: Rc<RefCell<HashMap<String, VirtualAxis>>>,
+}
+
+impl ActionMap {
+    /// Creates a map without any actions
+    pub fn new() -> ActionMap {
+        ActionMap {
+            bindings: Rc::new(RefCell::new(HashMap::new())),
+            axes: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    /// Adds a binding to an action, creating the action if needed
+    pub fn bind(&self, action: &str, binding: Binding) {
+        let mut bindings = self.bindings.borrow_mut();
+        let list = bindings.entry(action.to_string()).or_insert(Vec::new());
+        if !list.contains(&binding) {
+            list.push(binding);
+        }
+    }
+
+    /// Removes a binding from an action, returns whether it was bound
+    pub fn unbind(&self, action: &str, binding: Binding) -> bool {
+        match self.bindings.borrow_mut().get_mut(action) {
+            Some(list) => {
+                let before = list.len();
+                list.retain(|b| *b != binding);
+                before != list.len()
+            },
+            None => false
+        }
+    }
+
+    /// Removes an action and all its bindings
+    pub fn remove(&self, action: &str) {
+        self.bindings.borrow_mut().remove(action);
+    }
+
+    /// The bindings of an action
+    pub fn bindings(&self, action: &str) -> Vec<Binding> {
+        self.bindings.borrow().get(action).cloned().unwrap_or(Vec::new())
+    }
+
+    /// The names of all actions, sorted
+    pub fn actions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.bindings.borrow().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Adds an axis under the given name, replacing any axis of that name.
+    /// Its value is reset, the smoothing starts over from 0.
+    pub fn bind_axis(&self, name: &str, mut axis: VirtualAxis) {
+        axis.reset();
+        self.axes.borrow_mut().insert(name.to_string(), axis);
+    }
+
+    /// Removes an axis
+    pub fn remove_axis(&self, name: &str) {
+        self.axes.borrow_mut().remove(name);
+    }
+
+    /// The names of all axes, sorted
+    pub fn axes(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.axes.borrow().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Writes all bindings in the config format
+    pub fn to_config(&self) -> String {
+        let mut config = String::new();
+        for action in self.actions() {
+            let bindings: Vec<String> = self.bindings(&action).iter()
+                .map(|b| b.to_string()).collect();
+            config.push_str(&format!("{} = {}\n", action, bindings.join(", ")));
+        }
+        config
+    }
+
+    /// Replaces all bindings with those in the given config. Nothing is
+    /// changed if the config contains any errors, an action appearing on two
+    /// lines is one of them. Bindings listed twice on a line are kept once.
+    pub fn load_config(&self, config: &str) -> Result<(), ActionError> {
+        let mut parsed = HashMap::new();
+        let mut lines = HashMap::new();
+        for (i, line) in config.lines().enumerate() {
+            let error = |message: String| ActionError::Parse { line: i + 1, message: message };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let action = parts.next().unwrap().trim();
+            let bindings = match parts.next() {
+                Some(bindings) => bindings,
+                None => return Err(error("Expected 'action = bindings'".to_string()))
+            };
+            if action.is_empty() {
+                return Err(error("Missing action name".to_string()));
+            }
+            if let Some(first) = lines.insert(action.to_string(), i + 1) {
+                return Err(error(format!("Action '{}' is already bound on line {}",
+                                         action, first)));
+            }
+
+            let mut list = Vec::new();
+            for binding in bindings.split(',').filter(|b| !b.trim().is_empty()) {
+                let binding = try!(binding.parse::<Binding>().map_err(&error));
+                if !list.contains(&binding) {
+                    list.push(binding);
+                }
+            }
+            parsed.insert(action.to_string(), list);
+        }
+
+        *self.bindings.borrow_mut() = parsed;
+        Ok(())
+    }
+
+    /// Writes all bindings to the given file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ActionError> {
+        let mut file = try!(File::create(path));
+        try!(file.write_all(self.to_config().as_bytes()));
+        Ok(())
+    }
+
+    /// Replaces all bindings with those in the given file
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<(), ActionError> {
+        let mut config = String::new();
+        let mut file = try!(File::open(path));
+        try!(file.read_to_string(&mut config));
+        self.load_config(&config)
+    }
+}
+
+/// The state of all actions of an `ActionMap`, using the same sequence as
+/// keys do in `Keys`.
+///
+/// Actions can be consumed just like keys. An action that is only held
+/// through inputs a scene has consumed is consumed as well once
+/// `consume_inputs` has been called, the `SceneManager` does so before it
+/// hands the actions to a scene.
+pub struct Actions {
+    map: ActionMap,
+    states: HashMap<String, (KeyState, f64)>,
+    /// The actions consumed during this tick
+    consumed: RefCell<Vec<String>>,
+    /// Whether all actions have been consumed during this tick
+    all_consumed: Cell<bool>,
+    /// The axes whose input has been consumed during this tick
+    consumed_axes: RefCell<Vec<String>>,
+    /// The axes as they are set up in the map, and their smoothed copies
+    axes: HashMap<String, (VirtualAxis, VirtualAxis)>,
+}
+
+impl Actions {
+    /// Creates a new actions struct for the given bindings
+    pub fn new(map: ActionMap) -> Actions {
+        Actions {
+            map: map,
+            states: HashMap::new(),
+            consumed: RefCell::new(Vec::new()),
+            all_consumed: Cell::new(false),
+            consumed_axes: RefCell::new(Vec::new()),
+            axes: HashMap::new(),
+        }
+    }
+
+    /// The bindings these actions are using
+    pub fn map(&self) -> &ActionMap {
+        &self.map
+    }
+
+    /// Gives you the KeyState of a given action
+    pub fn status(&self, action: &str) -> KeyState {
+        match self.raw_status(action) {
+            KeyState::Pressed(t) | KeyState::Held(t) if self.is_consumed(action) =>
+                KeyState::NotPressed(t),
+            state => state
+        }
+    }
+
+    /// Marks an action as handled, it reads as not pressed until the next
+    /// tick
+    pub fn consume(&self, action: &str) {
+        self.consumed.borrow_mut().push(action.to_string());
+    }
+
+    /// Marks all actions and axes as handled until the next tick
+    pub fn consume_all(&self) {
+        self.all_consumed.set(true);
+    }
+
+    /// Whether an action has been consumed during this tick
+    pub fn is_consumed(&self, action: &str) -> bool {
+        self.all_consumed.get() || self.consumed.borrow().iter().any(|a| a == action)
+    }
+
+    /// Consumes every held action that none of its bindings holds anymore,
+    /// because the keys and buttons behind it have been consumed. Axes read
+    /// 0 once any of their input has been consumed.
+    pub fn consume_inputs(&self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads) {
+        for (name, &(_, ref axis)) in self.axes.iter() {
+            if axis.is_consumed(keys, mouse, gamepads)
+                && !self.consumed_axes.borrow().contains(name) {
+                self.consumed_axes.borrow_mut().push(name.clone());
+            }
+        }
+
+        let bindings = self.map.bindings.borrow();
+        for (action, list) in bindings.iter() {
+            let held = match self.raw_status(action) {
+                KeyState::Pressed(_) | KeyState::Held(_) => true,
+                KeyState::Released(_) | KeyState::NotPressed(_) => false
+            };
+            if held && !self.is_consumed(action)
+                && !list.iter().any(|b| b.evaluate(keys, mouse, gamepads).0) {
+                self.consume(action);
+            }
+        }
+    }
+
+    /// A quick way to check if a given action is pressed or held
+    pub fn held(&self, action: &str) -> bool {
+        match self.status(action) {
+            KeyState::Pressed(_)  | KeyState::Held(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given action has just been pressed
+    pub fn pressed(&self, action: &str) -> bool {
+        match self.status(action) {
+            KeyState::Pressed(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given action has just been released
+    pub fn released(&self, action: &str) -> bool {
+        match self.status(action) {
+            KeyState::Released(_) => { true },
+            KeyState::Pressed(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// The value of an action, buttons and keys give 0 or 1, axes anything
+    /// in between. With several bindings the one furthest from 0 wins.
+    pub fn value(&self, action: &str) -> f64 {
+        if self.is_consumed(action) {
+            return 0.0;
+        }
+        self.states.get(action).map_or(0.0, |&(_, v)| v)
+    }
+
+    /// The smoothed value of a named axis as of the last update, 0 if there
+    /// is no such axis or its input has been consumed
+    pub fn axis(&self, name: &str) -> f64 {
+        let consumed = self.consumed_axes.borrow().iter().any(|a| a == name);
+        if consumed || self.all_consumed.get() {
+            return 0.0;
+        }
+        self.axes.get(name).map_or(0.0, |&(_, ref axis)| axis.value())
+    }
+
+    /// Moves the named axes towards the current input for a step of `dt`
+    /// seconds. `Game` passes the step of the simulation, so the axes stand
+    /// still while it is paused and follow its time scale.
+    pub fn update_axes(&mut self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads, dt: f64) {
+        let definitions = self.map.axes.borrow();
+        let removed: Vec<String> = self.axes.keys()
+            .filter(|name| !definitions.contains_key(*name))
+            .cloned().collect();
+        for name in removed {
+            self.axes.remove(&name);
+        }
+        for (name, definition) in definitions.iter() {
+            let entry = self.axes.entry(name.clone()).or_insert((*definition, *definition));
+            // Start over if the axis has been bound anew
+            if entry.0 != *definition {
+                *entry = (*definition, *definition);
+            }
+            entry.1.update(keys, mouse, gamepads, dt);
+        }
+    }
+
+    /// Advances all actions to the next step and applies the current input
+    pub fn update(&mut self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads, time: f64) {
+        self.consumed.borrow_mut().clear();
+        self.consumed_axes.borrow_mut().clear();
+        self.all_consumed.set(false);
+
+        let bindings = self.map.bindings.borrow();
+        let removed: Vec<String> = self.states.keys()
+            .filter(|action| !bindings.contains_key(*action))
+            .cloned().collect();
+        for action in removed {
+            self.states.remove(&action);
+        }
+
+        for (action, list) in bindings.iter() {
+            let mut held = false;
+            let mut value: f64 = 0.0;
+            for binding in list {
+                let (h, v) = binding.evaluate(keys, mouse, gamepads);
+                held = held || h;
+                if v.abs() > value.abs() {
+                    value = v;
+                }
+            }
+
+            let entry = self.states.entry(action.clone())
+                .or_insert((KeyState::NotPressed(0.0), 0.0));
+            let change = if held { ElementState::Pressed } else { ElementState::Released };
+            *entry = (entry.0.next().transition(change, time), value);
+        }
+    }
+
+    /// The state of an action, no matter whether it has been consumed
+    fn raw_status(&self, action: &str) -> KeyState {
+        self.states.get(action).map_or(KeyState::NotPressed(0.0), |&(s, _)| s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::ElementState;
+    use event::{Keys, KeyCode, Mouse, MouseButton, Gamepads, PhysicalKey};
+    use event::gamepad::{Button, Axis, GamepadEvent};
+    use event::axis::{AxisSource, VirtualAxis};
+    use event::names::key_from_index;
+
+    #[test]
+    fn config_round_trip() {
+        let map = ActionMap::new();
+        map.bind("jump", Binding::Key(KeyCode::Space));
+        map.bind("jump", Binding::Button(Button::South));
+        map.bind("fire", Binding::Mouse(MouseButton::Left));
+        map.bind("move_x", Binding::KeyAxis(KeyCode::Left, KeyCode::Right));
+        map.bind("move_x", Binding::Axis(Axis::LeftX));
+        map.bind("brake", Binding::AxisPositive(Axis::LeftTrigger));
+        map.bind("forward", Binding::Physical(PhysicalKey::W));
+
+        let config = map.to_config();
+        assert_eq!(config, "brake = axis+:LeftTrigger\n\
+                            fire = mouse:Left\n\
+                            forward = physical:W\n\
+                            jump = key:Space, button:South\n\
+                            move_x = keys:Left:Right, axis:LeftX\n");
+
+        let loaded = ActionMap::new();
+        loaded.load_config(&format!("# A comment\n\n{}", config)).unwrap();
+        assert_eq!(loaded.to_config(), config);
+    }
+
+    #[test]
+    fn every_key_round_trips() {
+        let all: Vec<KeyCode> = (0..).map(key_from_index)
+            .take_while(|key| key.is_some())
+            .map(|key| key.unwrap())
+            .collect();
+        assert_eq!(all.len(), KeyCode::Yen as usize + 1);
+
+        let map = ActionMap::new();
+        for &key in all.iter() {
+            let binding = Binding::Key(key);
+            assert_eq!(binding.to_string().parse::<Binding>(), Ok(binding));
+            map.bind("press", binding);
+        }
+
+        let loaded = ActionMap::new();
+        loaded.load_config(&map.to_config()).unwrap();
+        assert_eq!(loaded.to_config(), map.to_config());
+    }
+
+    #[test]
+    fn config_errors() {
+        let map = ActionMap::new();
+        map.bind("jump", Binding::Key(KeyCode::Space));
+
+        match map.load_config("jump = key:Space\nduck = key:Nope") {
+            Err(ActionError::Parse { line: 2, .. }) => {},
+            other => panic!("Unexpected result {:?}", other)
+        }
+        assert!(map.load_config("jump").is_err());
+        match map.load_config("jump = key:Space\n# Again\njump = key:Up") {
+            Err(ActionError::Parse { line: 3, ref message }) =>
+                assert_eq!(&message[..], "Action 'jump' is already bound on line 1"),
+            other => panic!("Unexpected result {:?}", other)
+        }
+
+        // Nothing changed
+        assert_eq!(map.bindings("jump"), vec![Binding::Key(KeyCode::Space)]);
+        assert!(map.bindings("duck").is_empty());
+
+        map.load_config("jump = key:Up, button:South, key:Up").unwrap();
+        assert_eq!(map.bindings("jump"),
+                   vec![Binding::Key(KeyCode::Up), Binding::Button(Button::South)]);
+    }
+
+    #[test]
+    fn action_states() {
+        let map = ActionMap::new();
+        map.bind("jump", Binding::Key(KeyCode::Space));
+        map.bind("jump", Binding::Button(Button::South));
+        map.bind("move_x", Binding::KeyAxis(KeyCode::Left, KeyCode::Right));
+        map.bind("move_x", Binding::Axis(Axis::LeftX));
+
+        let mut actions = Actions::new(map.clone());
+        let mut keys = Keys::new();
+        let mouse = Mouse::new();
+        let mut pads = Gamepads::new();
+
+        keys.update_key(KeyCode::Space, ElementState::Pressed, 1.0);
+        keys.update_key(KeyCode::Left, ElementState::Pressed, 1.0);
+        actions.update(&keys, &mouse, &pads, 1.0);
+        assert!(actions.pressed("jump"));
+        assert_eq!(actions.value("move_x"), -1.0);
+
+        // Releasing the key while the gamepad button is still down keeps
+        // the action held
+        keys.update(2.0);
+        pads.handle(GamepadEvent::Button(0, Button::South, ElementState::Pressed), 2.0);
+        pads.handle(GamepadEvent::Axis(0, Axis::LeftX, 0.25), 2.0);
+        keys.update_key(KeyCode::Space, ElementState::Released, 2.0);
+        actions.update(&keys, &mouse, &pads, 2.0);
+        assert!(actions.held("jump"));
+        assert!(!actions.pressed("jump"));
+
+        keys.update(3.0);
+        keys.update_key(KeyCode::Left, ElementState::Released, 3.0);
+        pads.update(3.0);
+        pads.handle(GamepadEvent::Button(0, Button::South, ElementState::Released), 3.0);
+        actions.update(&keys, &mouse, &pads, 3.0);
+        assert!(actions.released("jump"));
+        assert_eq!(actions.value("move_x"), 0.25);
+
+        map.remove("jump");
+        actions.update(&keys, &mouse, &pads, 4.0);
+        assert!(!actions.held("jump"));
+    }
+
+    #[test]
+    fn named_axes() {
+        let map = ActionMap::new();
+        let source = AxisSource::Keys(KeyCode::Left, KeyCode::Right);
+        map.bind_axis("steer", VirtualAxis::new(source).acceleration(2.0));
+        assert_eq!(map.axes(), vec!["steer".to_string()]);
+
+        // Both smooth on their own, neither speeds up the other
+        let mut actions = Actions::new(map.clone());
+        let mut other = Actions::new(map.clone());
+        let mut keys = Keys::new();
+        let mouse = Mouse::new();
+        let pads = Gamepads::new();
+
+        keys.update(1.0);
+        keys.update_key(KeyCode::Right, ElementState::Pressed, 1.0);
+        actions.update_axes(&keys, &mouse, &pads, 0.0);
+        other.update_axes(&keys, &mouse, &pads, 0.0);
+        assert_eq!(actions.axis("steer"), 0.0);
+        actions.update_axes(&keys, &mouse, &pads, 0.25);
+        other.update_axes(&keys, &mouse, &pads, 0.25);
+        assert_eq!(actions.axis("steer"), 0.5);
+        assert_eq!(other.axis("steer"), 0.5);
+        actions.update_axes(&keys, &mouse, &pads, 0.75);
+        assert_eq!(actions.axis("steer"), 1.0);
+        assert_eq!(other.axis("steer"), 0.5);
+
+        // A consumed key stops driving the axis for the rest of the tick
+        keys.consume(KeyCode::Right);
+        actions.consume_inputs(&keys, &mouse, &pads);
+        assert_eq!(actions.axis("steer"), 0.0);
+        keys.update(2.0);
+        actions.update(&keys, &mouse, &pads, 2.0);
+        assert_eq!(actions.axis("steer"), 1.0);
+
+        map.remove_axis("steer");
+        actions.update_axes(&keys, &mouse, &pads, 0.5);
+        assert_eq!(actions.axis("steer"), 0.0);
+    }
+}
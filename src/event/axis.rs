@@ -0,0 +1,363 @@
+use super::{Keys, KeyCode, Mouse, Gamepads};
+use super::gamepad::{Axis, Gamepad};
+
+/// How small movements of a stick are ignored, so a worn stick that does not
+/// center perfectly doesn't make things drift
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Deadzone {
+    /// Every movement counts
+    None,
+    /// Each direction of the stick is ignored below the given value on its
+    /// own. This makes it easy to move along a single direction, but turns
+    /// the range of the stick into the shape of a cross.
+    Axial(f64),
+    /// Movements of the stick shorter than the given length are ignored,
+    /// this keeps diagonal movement smooth
+    Radial(f64),
+}
+
+impl Deadzone {
+    /// Applies the deadzone to a stick position, the remaining range is
+    /// scaled back up to reach from 0 to 1
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        match *self {
+            Deadzone::None => (x, y),
+            Deadzone::Axial(size) => (rescale(x, size), rescale(y, size)),
+            Deadzone::Radial(size) => {
+                let length = x.hypot(y);
+                if length <= size {
+                    return (0.0, 0.0);
+                }
+                let scaled = rescale(length, size);
+                (x / length * scaled, y / length * scaled)
+            }
+        }
+    }
+}
+
+/// Removes `size` from the distance to 0 and stretches the rest back to 1
+fn rescale(value: f64, size: f64) -> f64 {
+    if value.abs() <= size || size >= 1.0 {
+        0.0
+    } else {
+        value.signum() * ((value.abs() - size) / (1.0 - size)).min(1.0)
+    }
+}
+
+/// Shapes how an axis responds to being pushed, applied to the distance
+/// from 0 so the direction is kept. Steeper curves give finer control near
+/// the center.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Curve {
+    /// The value is passed on as is
+    Linear,
+    /// The value is squared
+    Quadratic,
+    /// The value is cubed
+    Cubic,
+    /// The value is raised to the given power
+    Power(f64),
+}
+
+impl Curve {
+    /// Applies the curve to a value between -1 and 1
+    pub fn apply(&self, value: f64) -> f64 {
+        let exponent = match *self {
+            Curve::Linear => return value,
+            Curve::Quadratic => 2.0,
+            Curve::Cubic => 3.0,
+            Curve::Power(exponent) => exponent,
+        };
+        if value == 0.0 {
+            0.0
+        } else {
+            value.signum() * value.abs().powf(exponent)
+        }
+    }
+}
+
+/// One of the two sticks of a gamepad
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Stick {
+    /// The left stick
+    Left,
+    /// The right stick
+    Right,
+}
+
+impl Stick {
+    /// The horizontal and the vertical axis of the stick
+    pub fn axes(&self) -> (Axis, Axis) {
+        match *self {
+            Stick::Left => (Axis::LeftX, Axis::LeftY),
+            Stick::Right => (Axis::RightX, Axis::RightY),
+        }
+    }
+
+    /// The position of the stick on the given gamepad, with the deadzone
+    /// applied to the position and the curve to its distance from the center
+    pub fn read(&self, pad: &Gamepad, deadzone: Deadzone, curve: Curve) -> (f64, f64) {
+        let (x_axis, y_axis) = self.axes();
+        let (x, y) = deadzone.apply(pad.axis(x_axis), pad.axis(y_axis));
+        let length = x.hypot(y);
+        if length == 0.0 {
+            return (0.0, 0.0);
+        }
+        let shaped = curve.apply(length.min(1.0));
+        (x / length * shaped, y / length * shaped)
+    }
+}
+
+/// A direction of a stick
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Left is -1, right is 1
+    Horizontal,
+    /// Up is -1, down is 1
+    Vertical,
+}
+
+/// Where a `VirtualAxis` takes its input from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AxisSource {
+    /// Two keys, the first one pushing towards -1 and the second one towards
+    /// 1. Holding both cancels out.
+    Keys(KeyCode, KeyCode),
+    /// One direction of a stick, on whichever gamepad pushes it furthest
+    Stick(Stick, Direction),
+    /// A single axis like a trigger, on whichever gamepad pushes it furthest
+    Gamepad(Axis),
+    /// The horizontal motion of the mouse during a tick in pixels, times the
+    /// given sensitivity
+    MouseX(f64),
+    /// The vertical motion of the mouse during a tick in pixels, times the
+    /// given sensitivity
+    MouseY(f64),
+}
+
+/// An axis giving a smoothed value each tick, built from keys, a gamepad or
+/// the mouse.
+///
+/// Per default the value follows the input immediately. With an
+/// `acceleration` it moves towards the input at that speed instead, and with
+/// a `snap_back` speed it returns towards 0 at that speed once the input is
+/// let go or reversed. This gives keyboard controls some of the feel of an
+/// analog stick.
+///
+/// ```ignore
+/// let mut move_x = VirtualAxis::new(AxisSource::Keys(KeyCode::Left, KeyCode::Right))
+///     .acceleration(4.0)
+///     .snap_back(8.0);
+/// let speed = move_x.update(&input.keys, &input.mouse, &input.gamepads, dt) * 200.0;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VirtualAxis {
+    source: AxisSource,
+    deadzone: Deadzone,
+    curve: Curve,
+    acceleration: f64,
+    snap_back: f64,
+    value: f64,
+}
+
+impl VirtualAxis {
+    /// Creates an axis that follows its source immediately, without any
+    /// deadzone
+    pub fn new(source: AxisSource) -> VirtualAxis {
+        VirtualAxis {
+            source: source,
+            deadzone: Deadzone::None,
+            curve: Curve::Linear,
+            acceleration: ::std::f64::INFINITY,
+            snap_back: ::std::f64::INFINITY,
+            value: 0.0,
+        }
+    }
+
+    /// Sets the deadzone used for gamepad sources
+    pub fn deadzone(mut self, deadzone: Deadzone) -> VirtualAxis {
+        self.deadzone = deadzone;
+        self
+    }
+
+    /// Sets the response curve used for gamepad sources
+    pub fn curve(mut self, curve: Curve) -> VirtualAxis {
+        self.curve = curve;
+        self
+    }
+
+    /// Sets how fast the value moves away from 0 towards the input, in units
+    /// per second
+    pub fn acceleration(mut self, per_second: f64) -> VirtualAxis {
+        self.acceleration = per_second;
+        self
+    }
+
+    /// Sets how fast the value moves back towards 0 once the input is let go
+    /// or reversed, in units per second
+    pub fn snap_back(mut self, per_second: f64) -> VirtualAxis {
+        self.snap_back = per_second;
+        self
+    }
+
+    /// Where the axis takes its input from
+    pub fn source(&self) -> AxisSource {
+        self.source
+    }
+
+    /// The smoothed value as of the last `update`
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Puts the value back to 0
+    pub fn reset(&mut self) {
+        self.value = 0.0;
+    }
+
+    /// The value the input asks for right now, before any smoothing
+    pub fn target(&self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads) -> f64 {
+        let furthest = |values: Vec<f64>| values.into_iter()
+            .fold(0.0, |best: f64, v| if v.abs() > best.abs() { v } else { best });
+
+        match self.source {
+            AxisSource::Keys(negative, positive) => {
+                let mut value = 0.0;
+                if keys.held(negative) {
+                    value -= 1.0;
+                }
+                if keys.held(positive) {
+                    value += 1.0;
+                }
+                value
+            },
+            AxisSource::Stick(stick, direction) => {
+                furthest(gamepads.all().iter().map(|pad| {
+                    let (x, y) = stick.read(pad, self.deadzone, self.curve);
+                    match direction {
+                        Direction::Horizontal => x,
+                        Direction::Vertical => y,
+                    }
+                }).collect())
+            },
+            AxisSource::Gamepad(axis) => {
+                furthest(gamepads.all().iter().map(|pad| {
+                    let (value, _) = self.deadzone.apply(pad.axis(axis), 0.0);
+                    self.curve.apply(value)
+                }).collect())
+            },
+            AxisSource::MouseX(sensitivity) => mouse.motion().0 as f64 * sensitivity,
+            AxisSource::MouseY(sensitivity) => mouse.motion().1 as f64 * sensitivity,
+        }
+    }
+
+    /// Whether any of the input of the source has been consumed during this
+    /// tick. Gamepad axes only count as consumed through `consume_all`.
+    pub fn is_consumed(&self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads) -> bool {
+        match self.source {
+            AxisSource::Keys(negative, positive) => {
+                keys.is_consumed(negative) || keys.is_consumed(positive)
+            },
+            AxisSource::Stick(_, _) | AxisSource::Gamepad(_) => {
+                gamepads.all().iter().any(|pad| pad.is_all_consumed())
+            },
+            AxisSource::MouseX(_) | AxisSource::MouseY(_) => mouse.is_all_consumed(),
+        }
+    }
+
+    /// Moves the value towards the current input for a tick of `dt` seconds
+    /// and returns it
+    pub fn update(&mut self, keys: &Keys, mouse: &Mouse, gamepads: &Gamepads, dt: f64) -> f64 {
+        let target = self.target(keys, mouse, gamepads);
+        self.value = if self.value * target < 0.0 {
+            approach(self.value, 0.0, self.snap_back, dt)
+        } else if target.abs() < self.value.abs() {
+            approach(self.value, target, self.snap_back, dt)
+        } else {
+            approach(self.value, target, self.acceleration, dt)
+        };
+        self.value
+    }
+}
+
+/// Moves `current` towards `target` by at most `speed * dt`
+fn approach(current: f64, target: f64, speed: f64, dt: f64) -> f64 {
+    let distance = target - current;
+    if speed.is_infinite() || distance.abs() <= speed * dt {
+        target
+    } else {
+        current + distance.signum() * speed * dt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::ElementState::{Pressed, Released};
+    use event::{Keys, KeyCode, Mouse, Gamepads};
+    use event::gamepad::{Axis, GamepadEvent};
+
+    #[test]
+    fn deadzones_and_curves() {
+        assert_eq!(Deadzone::Axial(0.5).apply(0.25, 0.75), (0.0, 0.5));
+        assert_eq!(Deadzone::Axial(0.5).apply(-1.0, 0.0), (-1.0, 0.0));
+        assert_eq!(Deadzone::Radial(0.5).apply(0.3, 0.3), (0.0, 0.0));
+        let (x, y) = Deadzone::Radial(0.5).apply(0.0, -0.75);
+        assert_eq!((x, y), (0.0, -0.5));
+
+        assert_eq!(Curve::Linear.apply(-0.5), -0.5);
+        assert_eq!(Curve::Quadratic.apply(-0.5), -0.25);
+        assert_eq!(Curve::Cubic.apply(0.5), 0.125);
+        assert_eq!(Curve::Power(2.0).apply(0.0), 0.0);
+    }
+
+    #[test]
+    fn key_axis_smoothing() {
+        let mouse = Mouse::new();
+        let pads = Gamepads::new();
+        let mut keys = Keys::new();
+        let mut axis = VirtualAxis::new(AxisSource::Keys(KeyCode::Left, KeyCode::Right))
+            .acceleration(2.0)
+            .snap_back(4.0);
+
+        keys.update(0.0);
+        keys.update_key(KeyCode::Right, Pressed, 0.0);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.25), 0.5);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.25), 1.0);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.25), 1.0);
+
+        keys.update(1.0);
+        keys.update_key(KeyCode::Right, Released, 1.0);
+        keys.update_key(KeyCode::Left, Pressed, 1.0);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.125), 0.5);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.125), 0.0);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.25), -0.5);
+
+        keys.update_key(KeyCode::Right, Pressed, 1.0);
+        assert_eq!(axis.target(&keys, &mouse, &pads), 0.0);
+        assert_eq!(axis.update(&keys, &mouse, &pads, 0.25), 0.0);
+    }
+
+    #[test]
+    fn gamepad_and_mouse_axes() {
+        let keys = Keys::new();
+        let mut mouse = Mouse::new();
+        let mut pads = Gamepads::new();
+        pads.handle(GamepadEvent::Connected(0), 0.0);
+        pads.handle(GamepadEvent::Axis(0, Axis::LeftX, 0.25), 0.0);
+        pads.handle(GamepadEvent::Axis(0, Axis::LeftY, -0.75), 0.0);
+
+        let mut stick = VirtualAxis::new(AxisSource::Stick(Stick::Left, Direction::Vertical))
+            .deadzone(Deadzone::Axial(0.5))
+            .curve(Curve::Quadratic);
+        assert_eq!(stick.update(&keys, &mouse, &pads, 0.1), -0.25);
+
+        let mut look = VirtualAxis::new(AxisSource::MouseX(0.5));
+        // The first position is not a motion, so this doesn't spike
+        mouse.update_position(10, 0);
+        mouse.update_position(30, 0);
+        assert_eq!(look.update(&keys, &mouse, &pads, 0.1), 10.0);
+        mouse.update(1.0);
+        assert_eq!(look.update(&keys, &mouse, &pads, 0.1), 0.0);
+    }
+}
@@ -0,0 +1,123 @@
+use super::{Keys, KeyCode};
+
+/// A pattern of key presses, so combos can be kept in a list and checked
+/// together. Each of them is only triggered during a single tick, see the
+/// methods of `Keys` they correspond to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Combo {
+    /// Keys pressed one after another within the given seconds, like a
+    /// fighting game motion. See `Keys::sequence`.
+    Sequence(Vec<KeyCode>, f64),
+    /// A key pressed twice within the given seconds, see
+    /// `Keys::double_tapped`
+    DoubleTap(KeyCode, f64),
+    /// A key held for the given seconds, see `Keys::long_pressed`
+    LongPress(KeyCode, f64),
+    /// Keys held down together, having been pressed within the given seconds
+    /// of each other. See `Keys::chord`.
+    Chord(Vec<KeyCode>, f64),
+}
+
+impl Combo {
+    /// Whether the combo has been completed this tick
+    pub fn triggered(&self, keys: &Keys) -> bool {
+        match *self {
+            Combo::Sequence(ref sequence, window) => keys.sequence(sequence, window),
+            Combo::DoubleTap(key, window) => keys.double_tapped(key, window),
+            Combo::LongPress(key, threshold) => keys.long_pressed(key, threshold),
+            Combo::Chord(ref chord, window) => keys.chord(chord, window),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::ElementState::{Pressed, Released};
+    use event::{Keys, KeyCode};
+
+    /// Presses and releases the key, returns whether the combo triggered
+    fn tap(keys: &mut Keys, combo: &Combo, key: KeyCode, time: f64) -> bool {
+        keys.update(time);
+        keys.update_key(key, Pressed, time);
+        let triggered = combo.triggered(keys);
+        keys.update(time + 0.05);
+        keys.update_key(key, Released, time + 0.05);
+        triggered
+    }
+
+    #[test]
+    fn held_and_released_for() {
+        let mut keys = Keys::new();
+        keys.update(1.0);
+        assert_eq!(keys.released_for(KeyCode::A), Some(1.0));
+        keys.update_key(KeyCode::A, Pressed, 1.0);
+        assert_eq!(keys.held_for(KeyCode::A), Some(0.0));
+
+        keys.update(1.5);
+        assert_eq!(keys.held_for(KeyCode::A), Some(0.5));
+        assert_eq!(keys.released_for(KeyCode::A), None);
+        keys.update_key(KeyCode::A, Released, 1.5);
+
+        keys.update(2.25);
+        assert_eq!(keys.held_for(KeyCode::A), None);
+        assert_eq!(keys.released_for(KeyCode::A), Some(0.75));
+    }
+
+    #[test]
+    fn double_tap_and_long_press() {
+        let double = Combo::DoubleTap(KeyCode::D, 0.3);
+        let mut keys = Keys::new();
+
+        keys.update(1.0);
+        keys.update_key(KeyCode::D, Pressed, 1.0);
+        assert!(!double.triggered(&keys));
+        keys.update(1.1);
+        keys.update_key(KeyCode::D, Released, 1.1);
+        keys.update(1.2);
+        keys.update_key(KeyCode::D, Pressed, 1.2);
+        assert!(double.triggered(&keys));
+
+        let long = Combo::LongPress(KeyCode::D, 0.5);
+        keys.update(1.6);
+        assert!(!long.triggered(&keys));
+        keys.update(1.8);
+        assert!(long.triggered(&keys));
+        keys.update(2.0);
+        assert!(!long.triggered(&keys));
+
+        keys.update_key(KeyCode::D, Released, 2.0);
+        keys.update(2.5);
+        keys.update_key(KeyCode::D, Pressed, 2.5);
+        assert!(!double.triggered(&keys));
+    }
+
+    #[test]
+    fn sequences_and_chords() {
+        let motion = Combo::Sequence(vec![KeyCode::Down, KeyCode::Right, KeyCode::J], 0.5);
+        let mut keys = Keys::new();
+
+        assert!(!tap(&mut keys, &motion, KeyCode::Down, 1.0));
+        assert!(!tap(&mut keys, &motion, KeyCode::Right, 1.1));
+        assert!(tap(&mut keys, &motion, KeyCode::J, 1.2));
+
+        tap(&mut keys, &motion, KeyCode::Down, 2.0);
+        tap(&mut keys, &motion, KeyCode::Left, 2.1);
+        tap(&mut keys, &motion, KeyCode::Right, 2.2);
+        assert!(!tap(&mut keys, &motion, KeyCode::J, 2.3));
+
+        tap(&mut keys, &motion, KeyCode::Down, 3.0);
+        tap(&mut keys, &motion, KeyCode::Right, 3.4);
+        assert!(!tap(&mut keys, &motion, KeyCode::J, 3.8));
+
+        let chord = Combo::Chord(vec![KeyCode::LControl, KeyCode::S], 0.2);
+        keys.update(4.0);
+        keys.update_key(KeyCode::LControl, Pressed, 4.0);
+        assert!(!chord.triggered(&keys));
+        keys.update(4.1);
+        keys.update_key(KeyCode::S, Pressed, 4.1);
+        assert!(chord.triggered(&keys));
+        keys.update(4.2);
+        assert!(!chord.triggered(&keys));
+    }
+}
@@ -0,0 +1,488 @@
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::rc::Rc;
+use glium::glutin::ElementState;
+use super::{KeyState, KeyStates};
+
+/// The buttons of a standard gamepad, face buttons are named after their
+/// position so they work for every brand of controller.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Button {
+    /// The bottom face button (A on Xbox, Cross on PlayStation)
+    South,
+    /// The right face button (B on Xbox, Circle on PlayStation)
+    East,
+    /// The left face button (X on Xbox, Square on PlayStation)
+    West,
+    /// The top face button (Y on Xbox, Triangle on PlayStation)
+    North,
+    /// The left shoulder button
+    LeftBumper,
+    /// The right shoulder button
+    RightBumper,
+    /// The left of the center buttons (Back, Select, Share)
+    Select,
+    /// The right of the center buttons (Start, Options)
+    Start,
+    /// The logo button in the middle
+    Guide,
+    /// Pressing down the left stick
+    LeftStick,
+    /// Pressing down the right stick
+    RightStick,
+    /// Up on the directional pad
+    DPadUp,
+    /// Down on the directional pad
+    DPadDown,
+    /// Left on the directional pad
+    DPadLeft,
+    /// Right on the directional pad
+    DPadRight,
+}
+
+const BUTTONS: usize = 15;
+
+const BUTTON_NAMES: [(Button, &'static str); BUTTONS] = [
+    (Button::South, "South"), (Button::East, "East"), (Button::West, "West"),
+    (Button::North, "North"), (Button::LeftBumper, "LeftBumper"),
+    (Button::RightBumper, "RightBumper"), (Button::Select, "Select"),
+    (Button::Start, "Start"), (Button::Guide, "Guide"),
+    (Button::LeftStick, "LeftStick"), (Button::RightStick, "RightStick"),
+    (Button::DPadUp, "DPadUp"), (Button::DPadDown, "DPadDown"),
+    (Button::DPadLeft, "DPadLeft"), (Button::DPadRight, "DPadRight"),
+];
+
+impl Button {
+    /// The name of the button, as used in config files
+    pub fn name(&self) -> &'static str {
+        BUTTON_NAMES[*self as usize].1
+    }
+
+    /// Looks up a button by its name
+    pub fn from_name(name: &str) -> Option<Button> {
+        BUTTON_NAMES.iter().find(|&&(_, n)| n == name).map(|&(b, _)| b)
+    }
+
+    /// Looks up a button by its position in this enum, `South` being 0
+    pub fn from_index(index: usize) -> Option<Button> {
+        BUTTON_NAMES.get(index).map(|&(b, _)| b)
+    }
+}
+
+/// The axes of a standard gamepad. Sticks go from -1 to 1 with positive
+/// values being right and down, triggers go from 0 (released) to 1.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// The left stick, horizontally
+    LeftX,
+    /// The left stick, vertically
+    LeftY,
+    /// The right stick, horizontally
+    RightX,
+    /// The right stick, vertically
+    RightY,
+    /// The left trigger
+    LeftTrigger,
+    /// The right trigger
+    RightTrigger,
+}
+
+const AXES: usize = 6;
+
+const AXIS_NAMES: [(Axis, &'static str); AXES] = [
+    (Axis::LeftX, "LeftX"), (Axis::LeftY, "LeftY"), (Axis::RightX, "RightX"),
+    (Axis::RightY, "RightY"), (Axis::LeftTrigger, "LeftTrigger"),
+    (Axis::RightTrigger, "RightTrigger"),
+];
+
+impl Axis {
+    /// The name of the axis, as used in config files
+    pub fn name(&self) -> &'static str {
+        AXIS_NAMES[*self as usize].1
+    }
+
+    /// Looks up an axis by its name
+    pub fn from_name(name: &str) -> Option<Axis> {
+        AXIS_NAMES.iter().find(|&&(_, n)| n == name).map(|&(a, _)| a)
+    }
+
+    /// Looks up an axis by its position in this enum, `LeftX` being 0
+    pub fn from_index(index: usize) -> Option<Axis> {
+        AXIS_NAMES.get(index).map(|&(a, _)| a)
+    }
+}
+
+/// Something that happened to one of the gamepads, identified by an id that
+/// stays the same while the gamepad is connected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad has been plugged in
+    Connected(usize),
+    /// A gamepad has been unplugged
+    Disconnected(usize),
+    /// A button of a gamepad has been pressed or released
+    Button(usize, Button, ElementState),
+    /// An axis of a gamepad moved to the given value
+    Axis(usize, Axis, f64),
+}
+
+/// Where gamepad events come from. Implement this to hook up a controller
+/// library, `Game::set_gamepad_backend` then polls it once per tick.
+pub trait GamepadBackend {
+    /// Returns all events since the last call, in the order they happened
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// A backend without any gamepads, this is the default.
+#[derive(Copy, Clone, Debug)]
+pub struct NoGamepads;
+
+impl GamepadBackend for NoGamepads {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        Vec::new()
+    }
+}
+
+/// An in-memory backend where gamepads are plugged in and buttons pressed by
+/// calling its methods, so input can be tested without any controller.
+///
+/// Clones share the same queue, so you can hand one to the `Game` and keep
+/// one to drive it.
+#[derive(Clone)]
+pub struct VirtualGamepads {
+    queue: Rc<RefCell<Vec<GamepadEvent>>>
+}
+
+impl VirtualGamepads {
+    /// Creates a backend without any gamepads
+    pub fn new() -> VirtualGamepads {
+        VirtualGamepads {
+            queue: Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+
+    /// Queues any event
+    pub fn send(&self, event: GamepadEvent) {
+        self.queue.borrow_mut().push(event);
+    }
+
+    /// Plugs in the gamepad with the given id
+    pub fn connect(&self, id: usize) {
+        self.send(GamepadEvent::Connected(id));
+    }
+
+    /// Unplugs the gamepad with the given id
+    pub fn disconnect(&self, id: usize) {
+        self.send(GamepadEvent::Disconnected(id));
+    }
+
+    /// Presses a button of the given gamepad
+    pub fn press(&self, id: usize, button: Button) {
+        self.send(GamepadEvent::Button(id, button, ElementState::Pressed));
+    }
+
+    /// Releases a button of the given gamepad
+    pub fn release(&self, id: usize, button: Button) {
+        self.send(GamepadEvent::Button(id, button, ElementState::Released));
+    }
+
+    /// Moves an axis of the given gamepad
+    pub fn move_axis(&self, id: usize, axis: Axis, value: f64) {
+        self.send(GamepadEvent::Axis(id, axis, value));
+    }
+}
+
+impl GamepadBackend for VirtualGamepads {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        mem::replace(&mut *self.queue.borrow_mut(), Vec::new())
+    }
+}
+
+/// The state of a single connected gamepad. Buttons go through the same
+/// sequence as keys do in `Keys`, and can be consumed just like them, see
+/// `Keys::consume`.
+pub struct Gamepad {
+    id: usize,
+    buttons: KeyStates,
+    axes: [f64; AXES],
+    consumed: RefCell<Vec<Button>>,
+    all_consumed: Cell<bool>,
+}
+
+impl Gamepad {
+    fn new(id: usize) -> Gamepad {
+        Gamepad {
+            id: id,
+            buttons: KeyStates::new(),
+            axes: [0.0; AXES],
+            consumed: RefCell::new(Vec::new()),
+            all_consumed: Cell::new(false),
+        }
+    }
+
+    /// The id the backend gave this gamepad
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Gives you the KeyState of a given button
+    pub fn status(&self, button: Button) -> KeyState {
+        match self.buttons.status(button as usize) {
+            KeyState::Pressed(t) | KeyState::Held(t) if self.is_consumed(button) =>
+                KeyState::NotPressed(t),
+            state => state
+        }
+    }
+
+    /// Marks a button as handled, it reads as not pressed until the next tick
+    pub fn consume(&self, button: Button) {
+        self.consumed.borrow_mut().push(button);
+    }
+
+    /// Marks all buttons and axes as handled until the next tick, the axes
+    /// read as 0 meanwhile
+    pub fn consume_all(&self) {
+        self.all_consumed.set(true);
+    }
+
+    /// Whether a button has been consumed during this tick
+    pub fn is_consumed(&self, button: Button) -> bool {
+        self.all_consumed.get() || self.consumed.borrow().contains(&button)
+    }
+
+    /// Whether `consume_all` has been called during this tick
+    pub fn is_all_consumed(&self) -> bool {
+        self.all_consumed.get()
+    }
+
+    /// A quick way to check if a given button is pressed or held
+    pub fn held(&self, button: Button) -> bool {
+        match self.status(button) {
+            KeyState::Pressed(_)  | KeyState::Held(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given button has just been pressed
+    pub fn pressed(&self, button: Button) -> bool {
+        match self.status(button) {
+            KeyState::Pressed(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given button has just been released
+    pub fn released(&self, button: Button) -> bool {
+        match self.status(button) {
+            KeyState::Released(_) => { true },
+            KeyState::Pressed(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// The current value of an axis
+    pub fn axis(&self, axis: Axis) -> f64 {
+        if self.all_consumed.get() { 0.0 } else { self.axes[axis as usize] }
+    }
+
+    fn update_button(&mut self, button: Button, state: ElementState, time: f64) {
+        self.buttons.update(button as usize, state, time);
+    }
+
+    fn update(&mut self) {
+        self.buttons.next();
+        self.consumed.borrow_mut().clear();
+        self.all_consumed.set(false);
+    }
+
+    fn release_all(&mut self, time: f64) {
+        self.buttons.release_all(time);
+    }
+}
+
+/// Holds the state of all connected gamepads
+pub struct Gamepads {
+    pads: Vec<Gamepad>,
+    connected: Vec<usize>,
+    disconnected: Vec<usize>,
+}
+
+impl Gamepads {
+    /// Creates a new gamepads struct without any gamepads
+    pub fn new() -> Gamepads {
+        Gamepads {
+            pads: Vec::new(),
+            connected: Vec::new(),
+            disconnected: Vec::new(),
+        }
+    }
+
+    /// The gamepad with the given id, if it is connected
+    pub fn get(&self, id: usize) -> Option<&Gamepad> {
+        self.pads.iter().find(|p| p.id == id)
+    }
+
+    /// The gamepad that has been connected the longest, handy for single
+    /// player games
+    pub fn first(&self) -> Option<&Gamepad> {
+        self.pads.first()
+    }
+
+    /// All connected gamepads, in the order they were connected
+    pub fn all(&self) -> &[Gamepad] {
+        &self.pads
+    }
+
+    /// The ids of the gamepads that have been connected during the last tick
+    pub fn connected(&self) -> &[usize] {
+        &self.connected
+    }
+
+    /// The ids of the gamepads that have been disconnected during the last
+    /// tick
+    pub fn disconnected(&self) -> &[usize] {
+        &self.disconnected
+    }
+
+    /// Applies an event from a backend. Events for gamepads that were never
+    /// connected connect them implicitly.
+    pub fn handle(&mut self, event: GamepadEvent, time: f64) {
+        match event {
+            GamepadEvent::Connected(id) => {
+                self.pad_mut(id);
+            },
+            GamepadEvent::Disconnected(id) => {
+                let before = self.pads.len();
+                self.pads.retain(|p| p.id != id);
+                if before != self.pads.len() {
+                    self.disconnected.push(id);
+                }
+            },
+            GamepadEvent::Button(id, button, state) => {
+                self.pad_mut(id).update_button(button, state, time);
+            },
+            GamepadEvent::Axis(id, axis, value) => {
+                self.pad_mut(id).axes[axis as usize] = value;
+            }
+        }
+    }
+
+    /// Consumes a button on every gamepad, see `Gamepad::consume`
+    pub fn consume(&self, button: Button) {
+        for pad in self.pads.iter() {
+            pad.consume(button);
+        }
+    }
+
+    /// Consumes all buttons and axes of every gamepad
+    pub fn consume_all(&self) {
+        for pad in self.pads.iter() {
+            pad.consume_all();
+        }
+    }
+
+    /// Releases all buttons of all gamepads, the axes keep their values
+    pub fn release_all(&mut self, time: f64) {
+        for pad in self.pads.iter_mut() {
+            pad.release_all(time);
+        }
+    }
+
+    /// Update all the gamepads and advance them to the next step, takes the
+    /// time of the new tick just like `Keys::update`
+    pub fn update(&mut self, _time: f64) {
+        for pad in self.pads.iter_mut() {
+            pad.update();
+        }
+        self.connected.clear();
+        self.disconnected.clear();
+    }
+
+    fn pad_mut(&mut self, id: usize) -> &mut Gamepad {
+        let found = self.pads.iter().position(|p| p.id == id);
+        let index = match found {
+            Some(index) => index,
+            None => {
+                self.pads.push(Gamepad::new(id));
+                self.connected.push(id);
+                self.pads.len() - 1
+            }
+        };
+        &mut self.pads[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::ElementState::{Pressed, Released};
+
+    #[test]
+    fn virtual_gamepad() {
+        let mut backend = VirtualGamepads::new();
+        let driver = backend.clone();
+        let mut pads = Gamepads::new();
+
+        driver.connect(3);
+        driver.press(3, Button::South);
+        driver.move_axis(3, Axis::LeftX, -0.5);
+        for ev in backend.poll() {
+            pads.handle(ev, 1.0);
+        }
+
+        assert_eq!(pads.connected(), &[3]);
+        assert!(pads.get(3).unwrap().pressed(Button::South));
+        assert_eq!(pads.first().unwrap().axis(Axis::LeftX), -0.5);
+
+        pads.update(2.0);
+        assert!(pads.get(3).unwrap().held(Button::South));
+        assert!(backend.poll().is_empty());
+
+        pads.consume(Button::South);
+        assert!(!pads.get(3).unwrap().held(Button::South));
+        pads.consume_all();
+        assert_eq!(pads.first().unwrap().axis(Axis::LeftX), 0.0);
+        pads.update(2.0);
+        assert!(pads.get(3).unwrap().held(Button::South));
+
+        // Losing the focus releases the buttons, but keeps the axes
+        pads.release_all(2.0);
+        assert!(pads.get(3).unwrap().released(Button::South));
+        assert_eq!(pads.first().unwrap().axis(Axis::LeftX), -0.5);
+
+        driver.release(3, Button::South);
+        driver.disconnect(3);
+        for ev in backend.poll() {
+            pads.handle(ev, 2.0);
+        }
+
+        assert!(pads.get(3).is_none());
+        assert_eq!(pads.disconnected(), &[3]);
+    }
+
+    #[test]
+    fn quick_tap() {
+        let mut pads = Gamepads::new();
+
+        pads.update(1.0);
+        pads.handle(GamepadEvent::Button(0, Button::East, Pressed), 1.0);
+        pads.handle(GamepadEvent::Button(0, Button::East, Released), 1.0);
+        assert!(pads.first().unwrap().pressed(Button::East));
+
+        pads.update(2.0);
+        assert!(pads.first().unwrap().released(Button::East));
+
+        pads.update(3.0);
+        assert!(!pads.first().unwrap().held(Button::East));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Button::DPadRight.name(), "DPadRight");
+        assert_eq!(Button::from_name("North"), Some(Button::North));
+        assert_eq!(Axis::RightTrigger.name(), "RightTrigger");
+        assert_eq!(Axis::from_name("LeftY"), Some(Axis::LeftY));
+        assert_eq!(Axis::from_name("Up"), None);
+    }
+}
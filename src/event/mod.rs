@@ -0,0 +1,898 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use vec_map::VecMap;
+use glium::glutin::{ElementState, Event};
+use self::gamepad::GamepadEvent;
+
+/// Re-Export Glutin VirtualKeyCodes
+pub use glium::glutin::VirtualKeyCode as KeyCode;
+
+pub use self::mouse::{Mouse, MouseButton};
+pub use self::gamepad::Gamepads;
+pub use self::action::{Actions, ActionMap, Binding};
+pub use self::names::{key_name, key_from_name};
+pub use self::text::{TextInput, TextEdit, TextField};
+pub use self::combo::Combo;
+pub use self::scancode::{Scancodes, PhysicalKey};
+pub use self::modifiers::{Modifier, Modifiers};
+pub use self::axis::VirtualAxis;
+
+mod mouse;
+mod names;
+mod text;
+mod scancode;
+mod modifiers;
+
+/// Named actions bound to keys, buttons and axes
+pub mod action;
+
+/// Key sequences, double-taps, long presses and chords
+pub mod combo;
+
+/// Analog axes built from keys, gamepad sticks and the mouse
+pub mod axis;
+
+/// Gamepads with a standard layout, and the backends they come from
+pub mod gamepad;
+
+/// Recording input and playing it back
+pub mod record;
+
+/// An enum allowing us to communicate what state a given Key is at. The `f64`
+/// in each variant tells you since when the key was last pressed. A value of 0
+/// indicating that it has never been pressed.
+#[derive(Copy, Clone, Debug)]
+pub enum KeyState {
+    /// The key has been pressed _this_ tick
+    Pressed(f64),
+    /// A key that has been held down for some time
+    Held(f64),
+    /// The key has just been released
+    Released(f64),
+    /// The Key is currently *not* pressed
+    NotPressed(f64)
+}
+
+impl KeyState {
+    /// The state in the following tick, `Pressed` turns into `Held` and
+    /// `Released` into `NotPressed`, both keeping their time
+    fn next(self) -> KeyState {
+        match self {
+            KeyState::Pressed(time) => KeyState::Held(time),
+            KeyState::Released(time) => KeyState::NotPressed(time),
+            state => state
+        }
+    }
+
+    /// The state after a button has been pressed or released at `time`.
+    /// Presses of buttons that are already down and releases of buttons
+    /// that are already up are ignored.
+    fn transition(self, state: ElementState, time: f64) -> KeyState {
+        match (state, self) {
+            (ElementState::Pressed, KeyState::NotPressed(_))
+                | (ElementState::Pressed, KeyState::Released(_)) => KeyState::Pressed(time),
+            (ElementState::Released, KeyState::Pressed(_))
+                | (ElementState::Released, KeyState::Held(_)) => KeyState::Released(time),
+            (_, current) => current
+        }
+    }
+}
+
+/// The states of a set of keys identified by a number, shared by `Keys`,
+/// `Scancodes`, `Mouse` and the gamepads
+struct KeyStates {
+    states: VecMap<KeyState>,
+    /// Keys that have been released during the tick they were pressed in,
+    /// they turn into `Released` with the next tick
+    pending: Vec<(usize, f64)>,
+}
+
+impl KeyStates {
+    fn new() -> KeyStates {
+        KeyStates {
+            states: VecMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn status(&self, index: usize) -> KeyState {
+        *self.states.get(&index).unwrap_or(&KeyState::NotPressed(0.0))
+    }
+
+    /// Applies a press or release, returns whether the key went down
+    fn update(&mut self, index: usize, state: ElementState, time: f64) -> bool {
+        let pending = self.pending.iter().position(|&(k, _)| k == index);
+        match (state, self.status(index)) {
+            (ElementState::Released, KeyState::Pressed(_)) => {
+                if pending.is_none() {
+                    self.pending.push((index, time));
+                }
+                false
+            },
+            (ElementState::Pressed, KeyState::Pressed(_)) => {
+                if let Some(i) = pending {
+                    self.pending.remove(i);
+                }
+                false
+            },
+            (state, current) => {
+                let next = current.transition(state, time);
+                self.states.insert(index, next);
+                match (current, next) {
+                    (KeyState::NotPressed(_), KeyState::Pressed(_))
+                        | (KeyState::Released(_), KeyState::Pressed(_)) => true,
+                    _ => false
+                }
+            }
+        }
+    }
+
+    fn release_all(&mut self, time: f64) {
+        for (index, state) in self.states.iter_mut() {
+            match *state {
+                KeyState::Pressed(_) => {
+                    if !self.pending.iter().any(|&(k, _)| k == index) {
+                        self.pending.push((index, time));
+                    }
+                },
+                KeyState::Held(_) => *state = KeyState::Released(time),
+                KeyState::Released(_) | KeyState::NotPressed(_) => {}
+            }
+        }
+    }
+
+    fn next(&mut self) {
+        for (_, value) in self.states.iter_mut() {
+            *value = value.next();
+        }
+        for (index, released) in self.pending.drain(..) {
+            self.states.insert(index, KeyState::Released(released));
+        }
+    }
+}
+
+/// Holds state about the currently pressed buttons as well as buttons that just
+/// have been pressed and those that are released.
+/// Sequence is as follows:
+///     1. NotPressed
+///     2. Pressed, for a single tick
+///     3. Held
+///     4. Released, for a single tick
+///     5. goto: 1
+///
+/// Every state lasts at least one tick, no matter how quickly the events
+/// arrive. Per default all keys are 'NotPressed'
+///
+/// A scene that handled a key can `consume` it, the key then reads as not
+/// pressed for the rest of the tick. This is how scenes lower on the stack,
+/// see `Scene::receives_covered_input`, only get the keys nobody above them
+/// wanted. Actions bound to consumed keys are consumed along with them, see
+/// `Actions::consume_inputs`.
+pub struct Keys {
+    keys: KeyStates,
+    /// The keys consumed during this tick
+    consumed: RefCell<Vec<KeyCode>>,
+    /// Whether all keys have been consumed during this tick
+    all_consumed: Cell<bool>,
+    scancodes: Scancodes,
+    text: TextInput,
+    /// The most recent presses, oldest first
+    history: VecDeque<(KeyCode, f64)>,
+    /// The time of the current tick
+    now: f64,
+    /// The time of the previous tick
+    last: f64,
+}
+
+/// How many presses `Keys` remembers for sequences and double-taps
+pub const HISTORY_LEN: usize = 32;
+
+impl Keys {
+    /// Gives you the KeyState of a given Key
+    pub fn status(&self, key: KeyCode) -> KeyState {
+        match self.keys.status(key as usize) {
+            KeyState::Pressed(t) | KeyState::Held(t) if self.is_consumed(key) =>
+                KeyState::NotPressed(t),
+            state => state
+        }
+    }
+
+    /// Marks a key as handled, it reads as not pressed until the next tick.
+    /// Its scancode is consumed as well.
+    pub fn consume(&self, key: KeyCode) {
+        self.consumed.borrow_mut().push(key);
+        self.scancodes.consume_key(key);
+    }
+
+    /// Marks all keys, scancodes and the typed text as handled until the
+    /// next tick, for scenes that want the keyboard to themselves
+    pub fn consume_all(&self) {
+        self.all_consumed.set(true);
+        self.scancodes.consume_all();
+        self.text.consume();
+    }
+
+    /// Whether a key has been consumed during this tick
+    pub fn is_consumed(&self, key: KeyCode) -> bool {
+        self.all_consumed.get() || self.consumed.borrow().contains(&key)
+    }
+
+    /// A quick way to check if a given key is pressed or held
+    pub fn held(&self, key: KeyCode) -> bool {
+        match self.status(key) {
+            KeyState::Pressed(_)  | KeyState::Held(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given key has just been pressed
+    pub fn pressed(&self, key: KeyCode) -> bool {
+        match self.status(key) {
+            KeyState::Pressed(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given key is released
+    pub fn not_pressed(&self, key: KeyCode) -> bool {
+        !self.pressed(key)
+    }
+
+    /// The modifier keys that are held down
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers::from_keys(self)
+    }
+
+    /// Whether a given key has just been pressed while exactly the given
+    /// modifiers are held, see `Modifiers::exactly`
+    pub fn pressed_with(&self, key: KeyCode, modifiers: &[Modifier]) -> bool {
+        self.pressed(key) && self.modifiers().exactly(modifiers)
+    }
+
+    /// Whether a given key is held while exactly the given modifiers are held
+    pub fn held_with(&self, key: KeyCode, modifiers: &[Modifier]) -> bool {
+        self.held(key) && self.modifiers().exactly(modifiers)
+    }
+
+    /// For how many seconds the key has been held down, `None` if it is up
+    pub fn held_for(&self, key: KeyCode) -> Option<f64> {
+        match self.status(key) {
+            KeyState::Pressed(t) | KeyState::Held(t) => Some(self.now - t),
+            KeyState::Released(_) | KeyState::NotPressed(_) => None
+        }
+    }
+
+    /// For how many seconds the key has been up, `None` if it is held down.
+    /// Keys that were never pressed count as up since the time 0.
+    pub fn released_for(&self, key: KeyCode) -> Option<f64> {
+        match self.status(key) {
+            KeyState::Released(t) | KeyState::NotPressed(t) => Some(self.now - t),
+            KeyState::Pressed(_) | KeyState::Held(_) => None
+        }
+    }
+
+    /// Whether the key has been pressed this tick, and had already been
+    /// pressed less than `window` seconds before that
+    pub fn double_tapped(&self, key: KeyCode, window: f64) -> bool {
+        self.pressed(key) && self.history.iter().rev()
+            .filter(|&&(k, _)| k == key)
+            .nth(1)
+            .map_or(false, |&(_, t)| self.now - t <= window)
+    }
+
+    /// Whether the key has been held for `threshold` seconds, this is only
+    /// true during the tick in which the threshold has been crossed
+    pub fn long_pressed(&self, key: KeyCode, threshold: f64) -> bool {
+        match self.status(key) {
+            KeyState::Pressed(t) | KeyState::Held(t) =>
+                self.now - t >= threshold && (self.last < t || self.last - t < threshold),
+            KeyState::Released(_) | KeyState::NotPressed(_) => false
+        }
+    }
+
+    /// Whether all keys are held down, having been pressed within `window`
+    /// seconds of each other. This is only true during the tick in which the
+    /// last of them has been pressed.
+    pub fn chord(&self, keys: &[KeyCode], window: f64) -> bool {
+        let mut first = ::std::f64::INFINITY;
+        let mut any_pressed = false;
+        for &key in keys {
+            match self.status(key) {
+                KeyState::Pressed(t) => {
+                    any_pressed = true;
+                    first = first.min(t);
+                },
+                KeyState::Held(t) => first = first.min(t),
+                KeyState::Released(_) | KeyState::NotPressed(_) => return false
+            }
+        }
+        any_pressed && self.now - first <= window
+    }
+
+    /// Whether the last presses were exactly the keys of `sequence` in that
+    /// order, all of them within `window` seconds. This is only true during
+    /// the tick in which the last key of the sequence has been pressed.
+    pub fn sequence(&self, sequence: &[KeyCode], window: f64) -> bool {
+        let last = match sequence.last() {
+            Some(&last) => last,
+            None => return false
+        };
+        if !self.pressed(last) || self.history.len() < sequence.len() {
+            return false;
+        }
+        let start = self.history.len() - sequence.len();
+        let recent: Vec<&(KeyCode, f64)> = self.history.iter().skip(start).collect();
+        recent.iter().zip(sequence.iter()).all(|(&&(k, _), &key)| k == key)
+            && self.now - recent[0].1 <= window
+    }
+
+    /// The text typed during this tick, call `start` on it to begin
+    /// collecting text
+    pub fn text(&self) -> &TextInput {
+        &self.text
+    }
+
+    /// Creates a new keys struct
+    pub fn new() -> Keys {
+        Keys {
+            keys: KeyStates::new(),
+            consumed: RefCell::new(Vec::new()),
+            all_consumed: Cell::new(false),
+            scancodes: Scancodes::new(),
+            text: TextInput::new(),
+            history: VecDeque::new(),
+            now: 0.0,
+            last: 0.0,
+        }
+    }
+
+    /// Adds a typed character to the text input
+    pub fn update_char(&mut self, c: char) {
+        self.text.character(c);
+    }
+
+    /// Update a given key with a given state. This never panics: presses of
+    /// keys that are already down (key repeat) and releases of keys that are
+    /// already up are ignored. A key that is pressed and released within the
+    /// same tick is reported as pressed during this tick and as released
+    /// during the next one.
+    pub fn update_key(&mut self, key: KeyCode, state: ElementState, time: f64) {
+        if let ElementState::Pressed = state {
+            self.text.key(key);
+        }
+
+        if self.keys.update(key as usize, state, time) {
+            if self.history.len() == HISTORY_LEN {
+                self.history.pop_front();
+            }
+            self.history.push_back((key, time));
+        }
+    }
+
+    /// Update the key at the given scancode, `key` is what the current
+    /// keyboard layout makes of it
+    pub fn update_scancode(&mut self, scancode: u8, key: Option<KeyCode>,
+                           state: ElementState, time: f64) {
+        self.scancodes.update(scancode, key, state, time);
+    }
+
+    /// The keys by their position on the keyboard instead of their meaning
+    pub fn scancodes(&self) -> &Scancodes {
+        &self.scancodes
+    }
+
+    /// Releases all keys that are held down, for when the window loses focus
+    /// and would not tell us about releases anymore
+    pub fn release_all(&mut self, time: f64) {
+        self.keys.release_all(time);
+        self.scancodes.release_all(time);
+    }
+
+    /// Update all the keys and advance them to the next step
+    pub fn update(&mut self, time: f64) {
+        self.keys.next();
+        self.consumed.borrow_mut().clear();
+        self.all_consumed.set(false);
+        self.scancodes.next();
+        self.text.update();
+        self.last = self.now;
+        self.now = time;
+    }
+}
+
+/// Something that happened since the last tick
+#[derive(Debug)]
+pub enum InputEvent {
+    /// An event of the window, as glutin reported it
+    Window(Event),
+    /// Something happened to a gamepad
+    Gamepad(GamepadEvent),
+}
+
+/// An input event together with the time it was received at, as told by the
+/// clock of the game when the event was polled. This can be earlier than the
+/// tick that hands it to the scenes, while the state of `Keys` and `Mouse`
+/// always changes at the time of the tick.
+#[derive(Debug)]
+pub struct TimedEvent {
+    /// When the event was received, in seconds
+    pub time: f64,
+    /// What happened
+    pub event: InputEvent,
+}
+
+/// Everything the player did since the last tick, this is what the
+/// `SceneManager` hands to the scenes.
+pub struct Input {
+    /// The state of the keyboard
+    pub keys: Keys,
+    /// The state of the mouse
+    pub mouse: Mouse,
+    /// The state of all connected gamepads
+    pub gamepads: Gamepads,
+    /// The state of all actions
+    pub actions: Actions,
+    /// All events received for this tick, in the order they arrived
+    pub events: Vec<TimedEvent>,
+}
+
+impl Input {
+    /// Creates a new input struct with nothing pressed
+    pub fn new() -> Input {
+        Input {
+            keys: Keys::new(),
+            mouse: Mouse::new(),
+            gamepads: Gamepads::new(),
+            actions: Actions::new(ActionMap::new()),
+            events: Vec::new(),
+        }
+    }
+
+    /// Releases all keys, mouse buttons and gamepad buttons, for when the
+    /// window loses focus
+    pub fn release_all(&mut self, time: f64) {
+        self.keys.release_all(time);
+        self.mouse.release_all(time);
+        self.gamepads.release_all(time);
+    }
+
+    /// Advance all input to the next step
+    pub fn update(&mut self, time: f64) {
+        self.keys.update(time);
+        self.mouse.update(time);
+        self.gamepads.update(time);
+        self.events.clear();
+    }
+
+    /// Derives the state of all actions from the other input, this has to
+    /// be called once all events of a tick have been applied.
+    pub fn update_actions(&mut self, time: f64) {
+        self.actions.update(&self.keys, &self.mouse, &self.gamepads, time);
+    }
+
+    /// Smooths the named axes of the actions for a step of `dt` seconds of
+    /// the simulation
+    pub fn update_axes(&mut self, dt: f64) {
+        self.actions.update_axes(&self.keys, &self.mouse, &self.gamepads, dt);
+    }
+}
+
+/// A StepResult should be returned by the closure given to one of the step
+/// functions.
+pub enum StepResult {
+    /// Signal that we should continue the event loop
+    Continue,
+    /// Signal that we should stop the event loop
+    Stop
+}
+
+/// A collection of methods meant to make it easy to get a quick and dirty
+/// event loop going.
+pub mod step {
+    use super::StepResult;
+    use clock::Clock;
+
+    /// A simple and stupid loop that tries to call `cb` after `step` time has
+    /// elapsed.
+    ///
+    /// `clock` is where the time is taken from, see `clock::MonotonicClock`.
+    /// `step` is in nanoseconds.
+    /// `cb` is a closure meant to return a `StepResult`
+    pub fn fixed<T>(clock: &Clock, step: u64, mut cb: T) where T: FnMut(f64) -> StepResult
+    {
+        let mut now = clock.now_ns();
+        loop {
+            if clock.now_ns() - now < step {
+                clock.sleep_ns(step - (clock.now_ns() - now));
+            }
+            let dt = clock.now_ns() - now;
+            if let StepResult::Stop = cb(dt as f64 / 1000_000_000. as f64) {
+                break;
+            }
+            now = clock.now_ns();
+        }
+    }
+
+    /// A curried version of `step::fixed`, trying to average 60 updates a
+    /// second.
+    /// See `step::fixed`
+    pub fn fixed_60<T>(clock: &Clock, cb: T) where T: FnMut(f64) -> StepResult {
+        fixed(clock, 16_666_667, cb)
+    }
+
+    /// Limits how much a step loop tries to catch up after a slow frame.
+    ///
+    /// Without limits a frame that took too long causes many updates (or one
+    /// huge `dt`) in the next frame, which then takes even longer. Time that
+    /// goes over a limit is dropped instead, the simulation then runs slower
+    /// than real time and the loop reports it as `Lag`.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct CatchUp {
+        /// The most updates that are run in a single frame
+        pub max_ticks: Option<u32>,
+        /// The most time in nanoseconds that is simulated for a single
+        /// frame, for variable timesteps this is the largest `dt`
+        pub max_frame_time: Option<u64>,
+    }
+
+    impl CatchUp {
+        /// Never drop any time
+        pub fn unlimited() -> CatchUp {
+            CatchUp {
+                max_ticks: None,
+                max_frame_time: None,
+            }
+        }
+
+        /// Clamps a frame time to `max_frame_time`, returning the clamped
+        /// time and how much was dropped
+        pub fn clamp(&self, frame_time: u64) -> (u64, u64) {
+            match self.max_frame_time {
+                Some(max) if frame_time > max => (max, frame_time - max),
+                _ => (frame_time, 0)
+            }
+        }
+    }
+
+    impl Default for CatchUp {
+        /// Simulates at most a quarter of a second per frame
+        fn default() -> CatchUp {
+            CatchUp {
+                max_ticks: None,
+                max_frame_time: Some(250_000_000),
+            }
+        }
+    }
+
+    /// Reported by a step loop whenever it had to drop time because of its
+    /// `CatchUp` limits, meaning the simulation fell behind.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Lag {
+        /// How long the frame really took, in nanoseconds
+        pub frame_time: u64,
+        /// How much time was not simulated, in nanoseconds
+        pub dropped: u64,
+    }
+
+    /// A fixed timestep loop with render interpolation.
+    ///
+    /// Elapsed wall-clock time is collected in an accumulator, `update` is
+    /// then called zero or more times with a constant `dt` of `step` until
+    /// less than one step is left over. Afterwards `render` is called exactly
+    /// once with the interpolation alpha (the leftover divided by `step`, so
+    /// in `[0, 1)`) which can be used to blend between the last two simulated
+    /// states.
+    ///
+    /// `clock` is where the time is taken from, see `clock::MonotonicClock`.
+    /// `step` is in nanoseconds.
+    /// `catch_up` limits the updates after slow frames, whenever time had to
+    /// be dropped `lagged` is called before `render`.
+    /// `state` is handed to all callbacks, so they can share it mutably.
+    /// The loop stops as soon as `update` or `render` return
+    /// `StepResult::Stop`.
+    pub fn accumulated<S, U, R, L>(clock: &Clock, step: u64, catch_up: CatchUp,
+                                   state: &mut S, mut update: U, mut render: R,
+                                   mut lagged: L)
+        where U: FnMut(&mut S, f64) -> StepResult,
+              R: FnMut(&mut S, f64) -> StepResult,
+              L: FnMut(&mut S, Lag)
+    {
+        let dt = step as f64 / 1000_000_000. as f64;
+        let mut accumulator = 0;
+        let mut last = clock.now_ns();
+        loop {
+            let now = clock.now_ns();
+            let frame_time = now - last;
+            last = now;
+
+            let (simulated, mut dropped) = catch_up.clamp(frame_time);
+            accumulator += simulated;
+
+            let mut ticks = 0;
+            while accumulator >= step {
+                if catch_up.max_ticks.map_or(false, |max| ticks >= max) {
+                    let behind = accumulator - accumulator % step;
+                    accumulator -= behind;
+                    dropped += behind;
+                    break;
+                }
+                if let StepResult::Stop = update(state, dt) {
+                    return;
+                }
+                accumulator -= step;
+                ticks += 1;
+            }
+
+            if dropped > 0 {
+                lagged(state, Lag { frame_time: frame_time, dropped: dropped });
+            }
+
+            if let StepResult::Stop = render(state, accumulator as f64 / step as f64) {
+                return;
+            }
+
+            // Nothing to do until the next update is due
+            let busy = clock.now_ns() - last;
+            if accumulator + busy < step {
+                clock.sleep_ns(step - accumulator - busy);
+            }
+        }
+    }
+
+    /// A curried version of `step::accumulated`, simulating 60 updates a
+    /// second with the default `CatchUp` limits and ignoring any lag.
+    /// See `step::accumulated`
+    pub fn accumulated_60<S, U, R>(clock: &Clock, state: &mut S, update: U, render: R)
+        where U: FnMut(&mut S, f64) -> StepResult,
+              R: FnMut(&mut S, f64) -> StepResult
+    {
+        accumulated(clock, 16_666_667, CatchUp::default(), state, update, render,
+                    |_state, _lag| {})
+    }
+
+    /// A loop with a variable timestep, `update` is called with the time that
+    /// passed since its last call and is directly followed by `render`.
+    ///
+    /// There is no sleeping at all, so this runs as fast as possible unless
+    /// `render` blocks (for example by waiting on vsync).
+    /// `catch_up` limits the `dt` through its `max_frame_time`, whenever time
+    /// had to be dropped `lagged` is called before `update`.
+    /// `state` is handed to all callbacks, so they can share it mutably.
+    /// The loop stops as soon as `update` or `render` return
+    /// `StepResult::Stop`.
+    pub fn variable<S, U, R, L>(clock: &Clock, catch_up: CatchUp, state: &mut S,
+                                mut update: U, mut render: R, mut lagged: L)
+        where U: FnMut(&mut S, f64) -> StepResult,
+              R: FnMut(&mut S) -> StepResult,
+              L: FnMut(&mut S, Lag)
+    {
+        let mut last = clock.now_ns();
+        loop {
+            let now = clock.now_ns();
+            let frame_time = now - last;
+            last = now;
+
+            let (dt, dropped) = catch_up.clamp(frame_time);
+            if dropped > 0 {
+                lagged(state, Lag { frame_time: frame_time, dropped: dropped });
+            }
+
+            if let StepResult::Stop = update(state, dt as f64 / 1000_000_000. as f64) {
+                return;
+            }
+
+            if let StepResult::Stop = render(state) {
+                return;
+            }
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use super::{step, StepResult, Keys, KeyState, KeyCode};
+    use super::step::{CatchUp, Lag};
+    use clock::{Clock, MonotonicClock, SimulatedClock};
+    use glium::glutin::ElementState;
+
+    #[test]
+    fn test_fixed_step() {
+        let mut t = 0;
+        step::fixed(&MonotonicClock, 1000, |_dt| {
+            t = 1;
+            StepResult::Stop
+        });
+
+        assert_eq!(1, t);
+    }
+
+    #[test]
+    fn test_fixed_60_step() {
+        let mut t = 0;
+        step::fixed_60(&MonotonicClock, |_dt| {
+            t = 1;
+            StepResult::Stop
+        });
+
+        assert_eq!(1, t);
+    }
+
+    #[test]
+    fn test_fixed_simulated_step() {
+        let clock = SimulatedClock::new();
+        let mut dts = Vec::new();
+        step::fixed(&clock, 1000, |dt| {
+            dts.push(dt);
+            if dts.len() == 3 { StepResult::Stop } else { StepResult::Continue }
+        });
+
+        assert_eq!(dts, vec![0.000001, 0.000001, 0.000001]);
+        assert_eq!(clock.now_ns(), 3000);
+    }
+
+    #[test]
+    fn test_accumulated_step() {
+        let mut dts = Vec::new();
+        step::accumulated(&MonotonicClock, 1_000_000, CatchUp::unlimited(), &mut dts,
+        |dts, dt| {
+            dts.push(dt);
+            if dts.len() == 5 {
+                StepResult::Stop
+            } else {
+                StepResult::Continue
+            }
+        }, |_dts, alpha| {
+            assert!(alpha >= 0.0 && alpha < 1.0);
+            StepResult::Continue
+        }, |_dts, _lag| {});
+
+        assert_eq!(5, dts.len());
+        for dt in dts {
+            assert_eq!(0.001, dt);
+        }
+    }
+
+    #[test]
+    fn test_accumulated_60_render_stop() {
+        let mut renders = 0;
+        step::accumulated_60(&MonotonicClock, &mut renders, |_renders, _dt| {
+            StepResult::Continue
+        }, |renders, _alpha| {
+            *renders += 1;
+            StepResult::Stop
+        });
+
+        assert_eq!(1, renders);
+    }
+
+    #[test]
+    fn test_variable_step() {
+        let clock = SimulatedClock::new();
+        let mut dts = Vec::new();
+        step::variable(&clock, CatchUp::unlimited(), &mut dts, |dts, dt| {
+            dts.push(dt);
+            if dts.len() == 3 { StepResult::Stop } else { StepResult::Continue }
+        }, |dts| {
+            clock.advance(dts.len() as u64 * 1000_000);
+            StepResult::Continue
+        }, |_dts, _lag| {});
+
+        assert_eq!(dts, vec![0.0, 0.001, 0.002]);
+    }
+
+    #[test]
+    fn test_variable_max_frame_time() {
+        let clock = SimulatedClock::new();
+        let catch_up = CatchUp { max_ticks: None, max_frame_time: Some(2000_000) };
+        let mut log = (Vec::new(), Vec::new());
+        step::variable(&clock, catch_up, &mut log, |log, dt| {
+            log.0.push(dt);
+            if log.0.len() == 2 { StepResult::Stop } else { StepResult::Continue }
+        }, |_log| {
+            clock.advance(5000_000);
+            StepResult::Continue
+        }, |log, lag| log.1.push(lag));
+
+        assert_eq!(log.0, vec![0.0, 0.002]);
+        assert_eq!(log.1, vec![Lag { frame_time: 5000_000, dropped: 3000_000 }]);
+    }
+
+    #[test]
+    fn test_accumulated_max_ticks() {
+        // Every update takes two and a half steps, so without a limit the
+        // loop would fall further behind with every frame
+        let clock = SimulatedClock::new();
+        let catch_up = CatchUp { max_ticks: Some(2), max_frame_time: None };
+        let mut log = (0, Vec::new());
+        step::accumulated(&clock, 1000, catch_up, &mut log, |log, _dt| {
+            clock.advance(2500);
+            log.0 += 1;
+            if log.0 == 6 { StepResult::Stop } else { StepResult::Continue }
+        }, |_log, _alpha| {
+            StepResult::Continue
+        }, |log, lag| log.1.push(lag));
+
+        assert_eq!(log.1, vec![Lag { frame_time: 5000, dropped: 3000 }]);
+    }
+
+    #[test]
+    fn test_key_timestamps() {
+        let clock = SimulatedClock::new();
+        let mut keys = Keys::new();
+
+        clock.advance_secs(1.0);
+        keys.update(clock.now());
+        keys.update_key(KeyCode::A, ElementState::Pressed, clock.now());
+
+        clock.advance_secs(0.5);
+        keys.update(clock.now());
+
+        match keys.status(KeyCode::A) {
+            KeyState::Held(t) => assert_eq!(t, 1.0),
+            s => panic!("Expected A to be held, got {:?}", s)
+        }
+    }
+
+    #[test]
+    fn test_key_same_tick() {
+        let mut keys = Keys::new();
+
+        keys.update(1.0);
+        keys.update_key(KeyCode::A, ElementState::Pressed, 1.0);
+        keys.update_key(KeyCode::A, ElementState::Released, 1.0);
+        assert!(keys.pressed(KeyCode::A));
+
+        keys.update(2.0);
+        assert!(!keys.pressed(KeyCode::A));
+        match keys.status(KeyCode::A) {
+            KeyState::Released(t) => assert_eq!(t, 1.0),
+            s => panic!("Expected A to be released, got {:?}", s)
+        }
+
+        keys.update_key(KeyCode::A, ElementState::Pressed, 2.0);
+        assert!(keys.pressed(KeyCode::A));
+        keys.update(3.0);
+        assert!(keys.held(KeyCode::A));
+    }
+
+    #[test]
+    fn test_key_out_of_order() {
+        let mut keys = Keys::new();
+
+        keys.update(1.0);
+        keys.update_key(KeyCode::B, ElementState::Released, 1.0);
+        assert!(!keys.held(KeyCode::B));
+
+        keys.update_key(KeyCode::B, ElementState::Pressed, 1.0);
+        keys.update_key(KeyCode::B, ElementState::Pressed, 1.0);
+        keys.update(2.0);
+        keys.update_key(KeyCode::B, ElementState::Pressed, 2.0);
+        match keys.status(KeyCode::B) {
+            KeyState::Held(t) => assert_eq!(t, 1.0),
+            s => panic!("Expected B to be held, got {:?}", s)
+        }
+
+        keys.update_key(KeyCode::B, ElementState::Released, 2.0);
+        keys.update_key(KeyCode::B, ElementState::Released, 2.0);
+        keys.update(3.0);
+        assert!(!keys.held(KeyCode::B));
+    }
+
+    #[test]
+    fn test_key_release_all() {
+        let mut keys = Keys::new();
+
+        keys.update(1.0);
+        keys.update_key(KeyCode::A, ElementState::Pressed, 1.0);
+        keys.update(2.0);
+        keys.update_key(KeyCode::B, ElementState::Pressed, 2.0);
+        keys.release_all(2.0);
+        assert!(keys.pressed(KeyCode::B));
+        assert!(!keys.held(KeyCode::A));
+
+        keys.update(3.0);
+        assert!(!keys.held(KeyCode::A));
+        assert!(!keys.held(KeyCode::B));
+    }
+}
@@ -0,0 +1,130 @@
+use super::{Keys, KeyCode};
+
+/// A modifier key, no matter whether it is the left or the right one
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    /// Either Shift key
+    Shift,
+    /// Either Control key
+    Ctrl,
+    /// Either Alt key
+    Alt,
+    /// Either logo key (Windows, Command, Super)
+    Logo,
+}
+
+const ALL: [Modifier; 4] = [Modifier::Shift, Modifier::Ctrl, Modifier::Alt, Modifier::Logo];
+
+/// Which modifier keys are held down, telling the left and right ones apart
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    /// The left Shift key
+    pub left_shift: bool,
+    /// The right Shift key
+    pub right_shift: bool,
+    /// The left Control key
+    pub left_ctrl: bool,
+    /// The right Control key
+    pub right_ctrl: bool,
+    /// The left Alt key
+    pub left_alt: bool,
+    /// The right Alt key, AltGr on many layouts
+    pub right_alt: bool,
+    /// The left logo key
+    pub left_logo: bool,
+    /// The right logo key
+    pub right_logo: bool,
+}
+
+impl Modifiers {
+    /// Reads the modifier keys that are currently held
+    pub fn from_keys(keys: &Keys) -> Modifiers {
+        Modifiers {
+            left_shift: keys.held(KeyCode::LShift),
+            right_shift: keys.held(KeyCode::RShift),
+            left_ctrl: keys.held(KeyCode::LControl),
+            right_ctrl: keys.held(KeyCode::RControl),
+            left_alt: keys.held(KeyCode::LAlt),
+            right_alt: keys.held(KeyCode::RAlt),
+            left_logo: keys.held(KeyCode::LWin),
+            right_logo: keys.held(KeyCode::RWin),
+        }
+    }
+
+    /// Whether either Shift key is held
+    pub fn shift(&self) -> bool {
+        self.left_shift || self.right_shift
+    }
+
+    /// Whether either Control key is held
+    pub fn ctrl(&self) -> bool {
+        self.left_ctrl || self.right_ctrl
+    }
+
+    /// Whether either Alt key is held
+    pub fn alt(&self) -> bool {
+        self.left_alt || self.right_alt
+    }
+
+    /// Whether either logo key is held
+    pub fn logo(&self) -> bool {
+        self.left_logo || self.right_logo
+    }
+
+    /// Whether either key of the given modifier is held
+    pub fn held(&self, modifier: Modifier) -> bool {
+        match modifier {
+            Modifier::Shift => self.shift(),
+            Modifier::Ctrl => self.ctrl(),
+            Modifier::Alt => self.alt(),
+            Modifier::Logo => self.logo(),
+        }
+    }
+
+    /// Whether any modifier is held
+    pub fn any(&self) -> bool {
+        ALL.iter().any(|&m| self.held(m))
+    }
+
+    /// Whether exactly the given modifiers are held and no others, so
+    /// `&[Modifier::Ctrl]` matches Ctrl+S but not Ctrl+Shift+S. An empty
+    /// slice matches when no modifier is held.
+    pub fn exactly(&self, modifiers: &[Modifier]) -> bool {
+        ALL.iter().all(|&m| self.held(m) == modifiers.contains(&m))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::ElementState::Pressed;
+    use event::{Keys, KeyCode, Mouse, MouseButton};
+
+    #[test]
+    fn shortcuts() {
+        let mut keys = Keys::new();
+        keys.update(1.0);
+        keys.update_key(KeyCode::S, Pressed, 1.0);
+        assert!(keys.pressed_with(KeyCode::S, &[]));
+        assert!(!keys.pressed_with(KeyCode::S, &[Modifier::Ctrl]));
+
+        keys.update(2.0);
+        keys.update_key(KeyCode::RControl, Pressed, 2.0);
+        keys.update(3.0);
+        keys.update_key(KeyCode::Z, Pressed, 3.0);
+        assert!(keys.pressed_with(KeyCode::Z, &[Modifier::Ctrl]));
+        assert!(!keys.pressed_with(KeyCode::Z, &[]));
+        assert!(keys.modifiers().right_ctrl);
+        assert!(!keys.modifiers().left_ctrl);
+
+        keys.update_key(KeyCode::LShift, Pressed, 3.0);
+        assert!(!keys.pressed_with(KeyCode::Z, &[Modifier::Ctrl]));
+        assert!(keys.pressed_with(KeyCode::Z, &[Modifier::Shift, Modifier::Ctrl]));
+
+        let mut mouse = Mouse::new();
+        mouse.update_button(MouseButton::Left, Pressed, 3.0);
+        mouse.set_modifiers(keys.modifiers());
+        assert!(mouse.pressed_with(MouseButton::Left, &[Modifier::Ctrl, Modifier::Shift]));
+        assert!(!mouse.pressed_with(MouseButton::Left, &[]));
+    }
+}
@@ -0,0 +1,306 @@
+use std::cell::{Cell, RefCell};
+use glium::glutin::{ElementState, MouseScrollDelta};
+use super::{KeyState, KeyStates, Modifier, Modifiers};
+
+/// Re-Export Glutin MouseButtons
+pub use glium::glutin::MouseButton;
+
+/// Holds state about the mouse cursor and its buttons.
+///
+/// Buttons go through the same sequence as keys do in `Keys`, so even a click
+/// that is pressed and released within one tick reads as pressed. The wheel
+/// and the motion are summed up over a single tick. Buttons can be consumed
+/// just like keys, see `Keys::consume`.
+pub struct Mouse {
+    /// Where the cursor is, unknown until it has been moved for the first
+    /// time
+    position: Option<(i32, i32)>,
+    motion: (i32, i32),
+    buttons: KeyStates,
+    consumed: RefCell<Vec<MouseButton>>,
+    all_consumed: Cell<bool>,
+    wheel: (f64, f64),
+    wheel_pixels: (f64, f64),
+    window_size: (u32, u32),
+    inside: bool,
+    was_inside: bool,
+    modifiers: Modifiers,
+}
+
+impl Mouse {
+    /// Creates a new mouse struct
+    pub fn new() -> Mouse {
+        Mouse {
+            position: None,
+            motion: (0, 0),
+            buttons: KeyStates::new(),
+            consumed: RefCell::new(Vec::new()),
+            all_consumed: Cell::new(false),
+            wheel: (0.0, 0.0),
+            wheel_pixels: (0.0, 0.0),
+            window_size: (0, 0),
+            inside: false,
+            was_inside: false,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    /// The position of the cursor in pixels, relative to the top left corner
+    /// of the window. This is (0, 0) until the cursor has been moved.
+    pub fn position(&self) -> (i32, i32) {
+        self.position.unwrap_or((0, 0))
+    }
+
+    /// How far the cursor moved during the last tick, the first position
+    /// reported doesn't count as motion
+    pub fn motion(&self) -> (i32, i32) {
+        if self.all_consumed.get() { (0, 0) } else { self.motion }
+    }
+
+    /// Gives you the KeyState of a given button
+    pub fn status(&self, button: MouseButton) -> KeyState {
+        match self.raw_status(button) {
+            KeyState::Pressed(t) | KeyState::Held(t) if self.is_consumed(button) =>
+                KeyState::NotPressed(t),
+            state => state
+        }
+    }
+
+    /// Marks a button as handled, it reads as not pressed until the next tick
+    pub fn consume(&self, button: MouseButton) {
+        self.consumed.borrow_mut().push(button);
+    }
+
+    /// Marks all buttons, the wheel and the motion as handled until the
+    /// next tick
+    pub fn consume_all(&self) {
+        self.all_consumed.set(true);
+    }
+
+    /// Whether `consume_all` has been called during this tick
+    pub fn is_all_consumed(&self) -> bool {
+        self.all_consumed.get()
+    }
+
+    /// Whether a button has been consumed during this tick
+    pub fn is_consumed(&self, button: MouseButton) -> bool {
+        self.all_consumed.get() || self.consumed.borrow().contains(&button)
+    }
+
+    /// A quick way to check if a given button is pressed or held
+    pub fn held(&self, button: MouseButton) -> bool {
+        match self.status(button) {
+            KeyState::Pressed(_)  | KeyState::Held(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given button has just been pressed
+    pub fn pressed(&self, button: MouseButton) -> bool {
+        match self.status(button) {
+            KeyState::Pressed(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given button has just been released
+    pub fn released(&self, button: MouseButton) -> bool {
+        match self.status(button) {
+            KeyState::Released(_) => { true },
+            KeyState::Pressed(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given button is not pressed
+    pub fn not_pressed(&self, button: MouseButton) -> bool {
+        !self.held(button)
+    }
+
+    /// How far the wheel was scrolled during the last tick, in lines
+    /// horizontally and vertically
+    pub fn wheel(&self) -> (f64, f64) {
+        if self.all_consumed.get() { (0.0, 0.0) } else { self.wheel }
+    }
+
+    /// How far a touchpad or precise wheel was scrolled during the last tick,
+    /// in pixels horizontally and vertically
+    pub fn wheel_pixels(&self) -> (f64, f64) {
+        if self.all_consumed.get() { (0.0, 0.0) } else { self.wheel_pixels }
+    }
+
+    /// Whether the cursor is over the window, as far as its last known
+    /// position tells. This is best-effort: the window stops getting told
+    /// about motion once the cursor is outside, so a cursor moved off quickly
+    /// may still count as inside.
+    pub fn inside(&self) -> bool {
+        self.inside
+    }
+
+    /// Whether the cursor moved onto the window during the last tick
+    pub fn entered(&self) -> bool {
+        self.inside && !self.was_inside
+    }
+
+    /// Whether the cursor moved off the window during the last tick, this is
+    /// best-effort just like `inside` and only fires if a position outside
+    /// the window has been reported
+    pub fn left(&self) -> bool {
+        !self.inside && self.was_inside
+    }
+
+    /// Update a given button with a given state
+    pub fn update_button(&mut self, button: MouseButton, state: ElementState, time: f64) {
+        self.buttons.update(button_index(button), state, time);
+    }
+
+    /// The modifier keys that were held down during this tick
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Whether a given button has just been pressed while exactly the given
+    /// modifiers are held, like Shift+Click
+    pub fn pressed_with(&self, button: MouseButton, modifiers: &[Modifier]) -> bool {
+        self.pressed(button) && self.modifiers.exactly(modifiers)
+    }
+
+    /// Sets the modifier keys that are held, `Game` copies them over from
+    /// `Keys` every tick
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    /// Releases all buttons that are held down
+    pub fn release_all(&mut self, time: f64) {
+        self.buttons.release_all(time);
+    }
+
+    /// Moves the cursor to the given position
+    pub fn update_position(&mut self, x: i32, y: i32) {
+        if let Some((last_x, last_y)) = self.position {
+            self.motion.0 += x - last_x;
+            self.motion.1 += y - last_y;
+        }
+        self.position = Some((x, y));
+        self.update_inside();
+    }
+
+    /// Adds the given scrolling to this tick
+    pub fn update_wheel(&mut self, delta: MouseScrollDelta) {
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => {
+                self.wheel.0 += x as f64;
+                self.wheel.1 += y as f64;
+            },
+            MouseScrollDelta::PixelDelta(x, y) => {
+                self.wheel_pixels.0 += x as f64;
+                self.wheel_pixels.1 += y as f64;
+            }
+        }
+    }
+
+    /// Tells the mouse how large the window is, which is needed to know
+    /// whether the cursor is inside of it
+    pub fn set_window_size(&mut self, width: u32, height: u32) {
+        self.window_size = (width, height);
+        self.update_inside();
+    }
+
+    /// Update all the buttons and advance them to the next step, this also
+    /// resets the motion and the wheel. Takes the time of the new tick just
+    /// like `Keys::update`.
+    pub fn update(&mut self, _time: f64) {
+        self.buttons.next();
+        self.consumed.borrow_mut().clear();
+        self.all_consumed.set(false);
+        self.motion = (0, 0);
+        self.wheel = (0.0, 0.0);
+        self.wheel_pixels = (0.0, 0.0);
+        self.was_inside = self.inside;
+    }
+
+    /// The state of a button, no matter whether it has been consumed
+    fn raw_status(&self, button: MouseButton) -> KeyState {
+        self.buttons.status(button_index(button))
+    }
+
+    fn update_inside(&mut self) {
+        let (w, h) = self.window_size;
+        self.inside = match self.position {
+            Some((x, y)) => x >= 0 && y >= 0 && (x as u32) < w && (y as u32) < h,
+            None => false
+        };
+    }
+}
+
+/// Where a button is kept in the `KeyStates` of the mouse
+fn button_index(button: MouseButton) -> usize {
+    match button {
+        MouseButton::Left => 0,
+        MouseButton::Right => 1,
+        MouseButton::Middle => 2,
+        MouseButton::Other(n) => 3 + n as usize,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::{ElementState, MouseScrollDelta};
+
+    #[test]
+    fn buttons_and_wheel() {
+        let mut mouse = Mouse::new();
+
+        mouse.update(1.0);
+        mouse.update_button(MouseButton::Left, ElementState::Pressed, 1.0);
+        mouse.update_wheel(MouseScrollDelta::LineDelta(0.0, 1.0));
+        mouse.update_wheel(MouseScrollDelta::LineDelta(0.0, 2.0));
+        assert!(mouse.pressed(MouseButton::Left));
+        assert!(mouse.not_pressed(MouseButton::Right));
+        assert_eq!(mouse.wheel(), (0.0, 3.0));
+
+        mouse.update(2.0);
+        assert!(mouse.held(MouseButton::Left));
+        assert!(!mouse.pressed(MouseButton::Left));
+        assert_eq!(mouse.wheel(), (0.0, 0.0));
+
+        mouse.update_button(MouseButton::Left, ElementState::Released, 2.0);
+        assert!(mouse.released(MouseButton::Left));
+    }
+
+    #[test]
+    fn quick_click() {
+        let mut mouse = Mouse::new();
+
+        mouse.update(1.0);
+        mouse.update_button(MouseButton::Right, ElementState::Pressed, 1.0);
+        mouse.update_button(MouseButton::Right, ElementState::Released, 1.0);
+        assert!(mouse.pressed(MouseButton::Right));
+
+        mouse.update(2.0);
+        assert!(mouse.released(MouseButton::Right));
+
+        mouse.update(3.0);
+        assert!(mouse.not_pressed(MouseButton::Right));
+    }
+
+    #[test]
+    fn enter_and_leave() {
+        let mut mouse = Mouse::new();
+        mouse.set_window_size(100, 100);
+
+        // Nothing to compare the first position with
+        mouse.update_position(10, 20);
+        assert!(mouse.entered());
+        assert_eq!(mouse.motion(), (0, 0));
+
+        mouse.update(0.0);
+        mouse.update_position(-5, 20);
+        assert!(mouse.left());
+        assert!(!mouse.inside());
+        assert_eq!(mouse.motion(), (-15, 0));
+    }
+}
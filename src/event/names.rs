@@ -0,0 +1,76 @@
+use super::KeyCode;
+
+macro_rules! key_names {
+    ($($key:ident),*) => {
+        /// Every key with its name, in the order the `KeyCode` variants are
+        /// declared in
+        const KEY_NAMES: &'static [(KeyCode, &'static str)] = &[
+            $((KeyCode::$key, stringify!($key))),*
+        ];
+    }
+}
+
+key_names!(
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X,
+    Y, Z,
+    Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15,
+    Snapshot, Scroll, Pause, Insert, Home, Delete, End, PageDown, PageUp,
+    Left, Up, Right, Down, Back, Return, Space,
+    Numlock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6,
+    Numpad7, Numpad8, Numpad9,
+    AbntC1, AbntC2, Add, Apostrophe, Apps, At, Ax, Backslash, Calculator,
+    Capital, Colon, Comma, Convert, Decimal, Divide, Equals, Grave, Kana,
+    Kanji, LAlt, LBracket, LControl, LMenu, LShift, LWin, Mail, MediaSelect,
+    MediaStop, Minus, Multiply, Mute, MyComputer, NavigateForward,
+    NavigateBackward, NextTrack, NoConvert, NumpadComma, NumpadEnter,
+    NumpadEquals, OEM102, Period, PlayPause, Power, PrevTrack, RAlt,
+    RBracket, RControl, RMenu, RShift, RWin, Semicolon, Slash, Sleep, Stop,
+    Subtract, Sysrq, Tab, Underline, Unlabeled, VolumeDown, VolumeUp, Wake,
+    WebBack, WebFavorites, WebForward, WebHome, WebRefresh, WebSearch,
+    WebStop, Yen
+);
+
+/// The name of a key, as used in config files. This is the name of the
+/// `KeyCode` variant, like "Space" or "F1". Every key has one.
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES.iter().find(|&&(k, _)| k == key).map(|&(_, name)| name)
+}
+
+/// Looks up a key by the name of its `KeyCode` variant, like "Space" or "F1"
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES.iter().find(|&&(_, n)| n == name).map(|&(key, _)| key)
+}
+
+/// The position of a key in the table of named keys, this stays the same
+/// between runs and is used to store keys compactly
+pub fn key_index(key: KeyCode) -> Option<usize> {
+    KEY_NAMES.iter().position(|&(k, _)| k == key)
+}
+
+/// Looks up a key by its position in the table of named keys
+pub fn key_from_index(index: usize) -> Option<KeyCode> {
+    KEY_NAMES.get(index).map(|&(key, _)| key)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use event::KeyCode;
+
+    #[test]
+    fn names_round_trip() {
+        assert_eq!(key_name(KeyCode::Space), Some("Space"));
+        assert_eq!(key_from_name("LShift"), Some(KeyCode::LShift));
+        assert_eq!(key_from_name("NoSuchKey"), None);
+
+        // The table covers every key, in order
+        assert_eq!(super::KEY_NAMES.len(), KeyCode::Yen as usize + 1);
+        for (i, &(key, name)) in super::KEY_NAMES.iter().enumerate() {
+            assert_eq!(key as usize, i);
+            assert_eq!(key_from_name(name), Some(key));
+            assert_eq!(format!("{:?}", key), name);
+            assert_eq!(key_index(key).and_then(key_from_index), Some(key));
+        }
+    }
+}
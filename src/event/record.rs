@@ -0,0 +1,570 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use glium::glutin::{Event, ElementState, MouseScrollDelta};
+use super::{KeyCode, MouseButton};
+use super::gamepad::{Button, Axis, GamepadEvent};
+use super::names::{key_index, key_from_index};
+
+const MAGIC: &'static [u8] = b"GGREC";
+
+/// The version of the file format, files of other versions are refused
+const VERSION: u8 = 2;
+
+/// Stands for a key without a virtual key code
+const NO_KEY: u8 = 255;
+
+/// A single piece of input that was fed into the game
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedEvent {
+    /// The window was asked to close
+    Close,
+    /// A key was pressed or released, given by its scancode and its virtual
+    /// key code if it has one
+    Key(ElementState, u8, Option<KeyCode>),
+    /// A character was typed
+    Char(char),
+    /// The cursor moved to the given position
+    MouseMoved(i32, i32),
+    /// A mouse button was pressed or released
+    MouseButton(ElementState, MouseButton),
+    /// The mouse wheel was scrolled by lines
+    MouseWheelLines(f32, f32),
+    /// The mouse wheel was scrolled by pixels
+    MouseWheelPixels(f32, f32),
+    /// The window was resized
+    Resized(u32, u32),
+    /// The window gained or lost focus
+    Focused(bool),
+    /// The application has been suspended or resumed
+    Suspended(bool),
+    /// A file has been dropped onto the window
+    DroppedFile(PathBuf),
+    /// Something happened to a gamepad
+    Gamepad(GamepadEvent),
+}
+
+impl RecordedEvent {
+    /// The recorded form of a window event, if the game cares about it
+    pub fn from_event(event: &Event) -> Option<RecordedEvent> {
+        match *event {
+            Event::Closed => Some(RecordedEvent::Close),
+            Event::KeyboardInput(state, scancode, key) =>
+                Some(RecordedEvent::Key(state, scancode, key)),
+            Event::ReceivedCharacter(c) => Some(RecordedEvent::Char(c)),
+            Event::MouseMoved((x, y)) => Some(RecordedEvent::MouseMoved(x, y)),
+            Event::MouseInput(state, button) => Some(RecordedEvent::MouseButton(state, button)),
+            Event::MouseWheel(MouseScrollDelta::LineDelta(x, y)) =>
+                Some(RecordedEvent::MouseWheelLines(x, y)),
+            Event::MouseWheel(MouseScrollDelta::PixelDelta(x, y)) =>
+                Some(RecordedEvent::MouseWheelPixels(x, y)),
+            Event::Resized(width, height) => Some(RecordedEvent::Resized(width, height)),
+            Event::Focused(focused) => Some(RecordedEvent::Focused(focused)),
+            Event::Suspended(suspended) => Some(RecordedEvent::Suspended(suspended)),
+            Event::DroppedFile(ref path) => Some(RecordedEvent::DroppedFile(path.clone())),
+            _ => None
+        }
+    }
+
+    /// The window event this was recorded from, `None` for gamepad events
+    pub fn to_event(&self) -> Option<Event> {
+        match *self {
+            RecordedEvent::Close => Some(Event::Closed),
+            RecordedEvent::Key(state, scancode, key) =>
+                Some(Event::KeyboardInput(state, scancode, key)),
+            RecordedEvent::Char(c) => Some(Event::ReceivedCharacter(c)),
+            RecordedEvent::MouseMoved(x, y) => Some(Event::MouseMoved((x, y))),
+            RecordedEvent::MouseButton(state, button) => Some(Event::MouseInput(state, button)),
+            RecordedEvent::MouseWheelLines(x, y) =>
+                Some(Event::MouseWheel(MouseScrollDelta::LineDelta(x, y))),
+            RecordedEvent::MouseWheelPixels(x, y) =>
+                Some(Event::MouseWheel(MouseScrollDelta::PixelDelta(x, y))),
+            RecordedEvent::Resized(width, height) => Some(Event::Resized(width, height)),
+            RecordedEvent::Focused(focused) => Some(Event::Focused(focused)),
+            RecordedEvent::Suspended(suspended) => Some(Event::Suspended(suspended)),
+            RecordedEvent::DroppedFile(ref path) => Some(Event::DroppedFile(path.clone())),
+            RecordedEvent::Gamepad(_) => None
+        }
+    }
+}
+
+/// Everything that went into a single tick
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// The time of the tick in nanoseconds, the one its input is stamped with
+    pub time: u64,
+    /// The step the tick was asked to advance by, before time control was
+    /// applied
+    pub dt: f64,
+    /// The input of the tick, in the order it arrived
+    pub events: Vec<RecordedEvent>,
+}
+
+/// An error while saving or loading a recording
+#[derive(Debug)]
+pub enum RecordingError {
+    /// The file could not be read or written
+    Io(io::Error),
+    /// The file is not a recording or it is damaged
+    Invalid(String),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RecordingError::Io(ref e) => write!(f, "Could not access the recording: {}", e),
+            RecordingError::Invalid(ref message) => write!(f, "Invalid recording: {}", message),
+        }
+    }
+}
+
+impl Error for RecordingError {
+    fn description(&self) -> &str {
+        match *self {
+            RecordingError::Io(ref e) => e.description(),
+            RecordingError::Invalid(_) => "invalid recording",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            RecordingError::Io(ref e) => Some(e),
+            RecordingError::Invalid(_) => None
+        }
+    }
+}
+
+impl From<io::Error> for RecordingError {
+    fn from(err: io::Error) -> RecordingError {
+        RecordingError::Io(err)
+    }
+}
+
+/// The input of a play session, tick by tick.
+///
+/// Get one from `Game::record` and save it once the game is over, then hand
+/// the loaded file to `Game::replay` to run the exact same session again.
+/// This is a cheap handle, clones share the same frames.
+///
+/// The file format is a compact binary one with a version number, files
+/// written by other versions of this library are refused. Virtual key codes
+/// are stored by their position in the table of named keys (see `key_name`),
+/// key events without one are only stored by their scancode.
+#[derive(Clone)]
+pub struct Recording {
+    frames: Rc<RefCell<Vec<Frame>>>
+}
+
+impl Recording {
+    /// Creates an empty recording
+    pub fn new() -> Recording {
+        Recording {
+            frames: Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+
+    /// The number of recorded ticks
+    pub fn len(&self) -> usize {
+        self.frames.borrow().len()
+    }
+
+    /// Whether no tick has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A copy of all recorded ticks
+    pub fn frames(&self) -> Vec<Frame> {
+        self.frames.borrow().clone()
+    }
+
+    /// Adds a tick to the end of the recording
+    pub fn push(&self, frame: Frame) {
+        self.frames.borrow_mut().push(frame);
+    }
+
+    /// Forgets all recorded ticks
+    pub fn clear(&self) {
+        self.frames.borrow_mut().clear();
+    }
+
+    /// Writes the recording in its binary format
+    pub fn write_to<W: Write>(&self, out: &mut W) -> Result<(), RecordingError> {
+        try!(out.write_all(MAGIC));
+        try!(out.write_all(&[VERSION]));
+        for frame in self.frames.borrow().iter() {
+            try!(write_u64(out, frame.time));
+            try!(write_u64(out, frame.dt.to_bits()));
+            try!(write_u32(out, frame.events.len() as u32));
+            for ev in frame.events.iter() {
+                try!(write_event(out, ev));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a recording in its binary format
+    pub fn read_from<R: Read>(input: &mut R) -> Result<Recording, RecordingError> {
+        let mut data = Vec::new();
+        try!(input.read_to_end(&mut data));
+        if !data.starts_with(MAGIC) {
+            return Err(RecordingError::Invalid("not a recording".to_string()));
+        }
+
+        let mut reader = Reader { data: &data, pos: MAGIC.len() };
+        match try!(reader.u8()) {
+            VERSION => {},
+            version => return Err(RecordingError::Invalid(
+                format!("unsupported version {}, expected {}", version, VERSION)))
+        }
+        let recording = Recording::new();
+        while reader.pos < data.len() {
+            let time = try!(reader.u64());
+            let dt = f64::from_bits(try!(reader.u64()));
+            let count = try!(reader.u32());
+            let mut events = Vec::new();
+            for _ in 0..count {
+                events.push(try!(reader.event()));
+            }
+            recording.push(Frame { time: time, dt: dt, events: events });
+        }
+        Ok(recording)
+    }
+
+    /// Saves the recording to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RecordingError> {
+        let mut file = try!(File::create(path));
+        self.write_to(&mut file)
+    }
+
+    /// Loads a recording from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Recording, RecordingError> {
+        let mut file = try!(File::open(path));
+        Recording::read_from(&mut file)
+    }
+}
+
+fn write_u32<W: Write>(out: &mut W, value: u32) -> io::Result<()> {
+    let bytes = [value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8];
+    out.write_all(&bytes)
+}
+
+fn write_u64<W: Write>(out: &mut W, value: u64) -> io::Result<()> {
+    try!(write_u32(out, value as u32));
+    write_u32(out, (value >> 32) as u32)
+}
+
+fn write_f32<W: Write>(out: &mut W, value: f32) -> io::Result<()> {
+    write_u32(out, value.to_bits())
+}
+
+fn state_byte(state: ElementState) -> u8 {
+    match state {
+        ElementState::Pressed => 1,
+        ElementState::Released => 0,
+    }
+}
+
+fn write_event<W: Write>(out: &mut W, event: &RecordedEvent) -> io::Result<()> {
+    match *event {
+        RecordedEvent::Close => out.write_all(&[0]),
+        RecordedEvent::Key(state, scancode, key) => {
+            let index = key.and_then(key_index).map_or(NO_KEY, |i| i as u8);
+            out.write_all(&[1, state_byte(state), scancode, index])
+        },
+        RecordedEvent::Char(c) => {
+            try!(out.write_all(&[2]));
+            write_u32(out, c as u32)
+        },
+        RecordedEvent::MouseMoved(x, y) => {
+            try!(out.write_all(&[3]));
+            try!(write_u32(out, x as u32));
+            write_u32(out, y as u32)
+        },
+        RecordedEvent::MouseButton(state, button) => {
+            let (code, other) = match button {
+                MouseButton::Left => (0, 0),
+                MouseButton::Right => (1, 0),
+                MouseButton::Middle => (2, 0),
+                MouseButton::Other(n) => (3, n),
+            };
+            out.write_all(&[4, state_byte(state), code, other])
+        },
+        RecordedEvent::MouseWheelLines(x, y) => {
+            try!(out.write_all(&[5]));
+            try!(write_f32(out, x));
+            write_f32(out, y)
+        },
+        RecordedEvent::MouseWheelPixels(x, y) => {
+            try!(out.write_all(&[6]));
+            try!(write_f32(out, x));
+            write_f32(out, y)
+        },
+        RecordedEvent::Resized(width, height) => {
+            try!(out.write_all(&[7]));
+            try!(write_u32(out, width));
+            write_u32(out, height)
+        },
+        RecordedEvent::Focused(focused) => out.write_all(&[12, focused as u8]),
+        RecordedEvent::Suspended(suspended) => out.write_all(&[13, suspended as u8]),
+        RecordedEvent::DroppedFile(ref path) => {
+            let path = path.to_string_lossy();
+            try!(out.write_all(&[14]));
+            try!(write_u32(out, path.len() as u32));
+            out.write_all(path.as_bytes())
+        },
+        RecordedEvent::Gamepad(GamepadEvent::Connected(id)) => {
+            try!(out.write_all(&[8]));
+            write_u32(out, id as u32)
+        },
+        RecordedEvent::Gamepad(GamepadEvent::Disconnected(id)) => {
+            try!(out.write_all(&[9]));
+            write_u32(out, id as u32)
+        },
+        RecordedEvent::Gamepad(GamepadEvent::Button(id, button, state)) => {
+            try!(out.write_all(&[10]));
+            try!(write_u32(out, id as u32));
+            out.write_all(&[button as u8, state_byte(state)])
+        },
+        RecordedEvent::Gamepad(GamepadEvent::Axis(id, axis, value)) => {
+            try!(out.write_all(&[11]));
+            try!(write_u32(out, id as u32));
+            try!(out.write_all(&[axis as u8]));
+            write_u64(out, value.to_bits())
+        },
+    }
+}
+
+/// Reads values back in the order `write_event` wrote them
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Result<u8, RecordingError> {
+        match self.data.get(self.pos) {
+            Some(&byte) => {
+                self.pos += 1;
+                Ok(byte)
+            },
+            None => Err(RecordingError::Invalid("unexpected end of file".to_string()))
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32, RecordingError> {
+        let mut value = 0;
+        for i in 0..4 {
+            value |= (try!(self.u8()) as u32) << (i * 8);
+        }
+        Ok(value)
+    }
+
+    fn u64(&mut self) -> Result<u64, RecordingError> {
+        let low = try!(self.u32()) as u64;
+        let high = try!(self.u32()) as u64;
+        Ok(low | high << 32)
+    }
+
+    /// The next `len` bytes, checking that there are that many left
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], RecordingError> {
+        if len > self.data.len() - self.pos {
+            return Err(RecordingError::Invalid("unexpected end of file".to_string()));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn f32(&mut self) -> Result<f32, RecordingError> {
+        Ok(f32::from_bits(try!(self.u32())))
+    }
+
+    fn state(&mut self) -> Result<ElementState, RecordingError> {
+        match try!(self.u8()) {
+            0 => Ok(ElementState::Released),
+            1 => Ok(ElementState::Pressed),
+            n => Err(RecordingError::Invalid(format!("unknown button state {}", n)))
+        }
+    }
+
+    fn event(&mut self) -> Result<RecordedEvent, RecordingError> {
+        let event = match try!(self.u8()) {
+            0 => RecordedEvent::Close,
+            1 => {
+                let state = try!(self.state());
+                let scancode = try!(self.u8());
+                let key = match try!(self.u8()) {
+                    NO_KEY => None,
+                    index => match key_from_index(index as usize) {
+                        Some(key) => Some(key),
+                        None => return Err(RecordingError::Invalid(format!("unknown key {}", index)))
+                    }
+                };
+                RecordedEvent::Key(state, scancode, key)
+            },
+            2 => {
+                let code = try!(self.u32());
+                match ::std::char::from_u32(code) {
+                    Some(c) => RecordedEvent::Char(c),
+                    None => return Err(RecordingError::Invalid(format!("invalid character {}", code)))
+                }
+            },
+            3 => RecordedEvent::MouseMoved(try!(self.u32()) as i32, try!(self.u32()) as i32),
+            4 => {
+                let state = try!(self.state());
+                let button = match (try!(self.u8()), try!(self.u8())) {
+                    (0, _) => MouseButton::Left,
+                    (1, _) => MouseButton::Right,
+                    (2, _) => MouseButton::Middle,
+                    (3, n) => MouseButton::Other(n),
+                    (code, _) =>
+                        return Err(RecordingError::Invalid(format!("unknown mouse button {}", code)))
+                };
+                RecordedEvent::MouseButton(state, button)
+            },
+            5 => RecordedEvent::MouseWheelLines(try!(self.f32()), try!(self.f32())),
+            6 => RecordedEvent::MouseWheelPixels(try!(self.f32()), try!(self.f32())),
+            7 => RecordedEvent::Resized(try!(self.u32()), try!(self.u32())),
+            8 => RecordedEvent::Gamepad(GamepadEvent::Connected(try!(self.u32()) as usize)),
+            9 => RecordedEvent::Gamepad(GamepadEvent::Disconnected(try!(self.u32()) as usize)),
+            10 => {
+                let id = try!(self.u32()) as usize;
+                let index = try!(self.u8());
+                let button = match Button::from_index(index as usize) {
+                    Some(button) => button,
+                    None => return Err(RecordingError::Invalid(format!("unknown button {}", index)))
+                };
+                RecordedEvent::Gamepad(GamepadEvent::Button(id, button, try!(self.state())))
+            },
+            11 => {
+                let id = try!(self.u32()) as usize;
+                let index = try!(self.u8());
+                let axis = match Axis::from_index(index as usize) {
+                    Some(axis) => axis,
+                    None => return Err(RecordingError::Invalid(format!("unknown axis {}", index)))
+                };
+                let value = f64::from_bits(try!(self.u64()));
+                RecordedEvent::Gamepad(GamepadEvent::Axis(id, axis, value))
+            },
+            12 => RecordedEvent::Focused(try!(self.u8()) != 0),
+            13 => RecordedEvent::Suspended(try!(self.u8()) != 0),
+            14 => {
+                let len = try!(self.u32()) as usize;
+                let bytes = try!(self.bytes(len));
+                match String::from_utf8(bytes.to_vec()) {
+                    Ok(path) => RecordedEvent::DroppedFile(PathBuf::from(path)),
+                    Err(_) => return Err(RecordingError::Invalid("invalid file name".to_string()))
+                }
+            },
+            n => return Err(RecordingError::Invalid(format!("unknown event {}", n)))
+        };
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+    use glium::glutin::ElementState;
+    use event::{KeyCode, MouseButton};
+    use event::gamepad::{Button, Axis, GamepadEvent};
+
+    #[test]
+    fn binary_round_trip() {
+        let recording = Recording::new();
+        recording.push(Frame {
+            time: 16_666_667,
+            dt: 0.016666667,
+            events: vec![
+                RecordedEvent::Key(ElementState::Pressed, 57, Some(KeyCode::Space)),
+                RecordedEvent::Key(ElementState::Released, 120, None),
+                RecordedEvent::Char('ö'),
+                RecordedEvent::MouseMoved(-4, 300),
+                RecordedEvent::MouseButton(ElementState::Released, MouseButton::Other(7)),
+                RecordedEvent::MouseWheelPixels(1.5, -2.0),
+                RecordedEvent::Resized(800, 600),
+                RecordedEvent::Focused(false),
+                RecordedEvent::Suspended(true),
+                RecordedEvent::DroppedFile(PathBuf::from("levels/übung.map")),
+            ]
+        });
+        recording.push(Frame {
+            time: 33_333_334,
+            dt: 0.016666667,
+            events: vec![
+                RecordedEvent::Gamepad(GamepadEvent::Connected(3)),
+                RecordedEvent::Gamepad(GamepadEvent::Button(3, Button::Start, ElementState::Pressed)),
+                RecordedEvent::Gamepad(GamepadEvent::Axis(3, Axis::RightY, -0.25)),
+                RecordedEvent::Close,
+            ]
+        });
+
+        let mut data = Vec::new();
+        recording.write_to(&mut data).unwrap();
+        let loaded = Recording::read_from(&mut &data[..]).unwrap();
+        assert_eq!(loaded.frames(), recording.frames());
+    }
+
+    #[test]
+    fn invalid_files() {
+        match Recording::read_from(&mut &b"not a recording"[..]) {
+            Err(RecordingError::Invalid(_)) => {},
+            _ => panic!("expected an invalid recording")
+        }
+
+        // A file name claiming to be longer than the file
+        let recording = Recording::new();
+        recording.push(Frame {
+            time: 0,
+            dt: 0.0,
+            events: vec![RecordedEvent::DroppedFile(PathBuf::from("a"))]
+        });
+        let mut data = Vec::new();
+        recording.write_to(&mut data).unwrap();
+        let len = data.len() - 5;
+        data[len..len + 4].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        match Recording::read_from(&mut &data[..]) {
+            Err(RecordingError::Invalid(_)) => {},
+            _ => panic!("expected an invalid recording")
+        }
+
+        // The first version stored keys without their scancode
+        match Recording::read_from(&mut &b"GGREC\x01\x00\x00"[..]) {
+            Err(RecordingError::Invalid(_)) => {},
+            _ => panic!("expected an invalid recording")
+        }
+
+        let recording = Recording::new();
+        recording.push(Frame { time: 0, dt: 0.0, events: vec![RecordedEvent::Close] });
+        let mut data = Vec::new();
+        recording.write_to(&mut data).unwrap();
+        data.pop();
+        data.push(42);
+        match Recording::read_from(&mut &data[..]) {
+            Err(RecordingError::Invalid(_)) => {},
+            _ => panic!("expected an invalid recording")
+        }
+
+        let recording = Recording::new();
+        recording.push(Frame {
+            time: 0,
+            dt: 0.0,
+            events: vec![RecordedEvent::MouseButton(ElementState::Pressed, MouseButton::Left)]
+        });
+        let mut data = Vec::new();
+        recording.write_to(&mut data).unwrap();
+        // The button code comes second to last
+        let code = data.len() - 2;
+        data[code] = 9;
+        match Recording::read_from(&mut &data[..]) {
+            Err(RecordingError::Invalid(_)) => {},
+            _ => panic!("expected an invalid recording")
+        }
+    }
+}
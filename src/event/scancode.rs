@@ -0,0 +1,395 @@
+use std::cell::{Cell, RefCell};
+use vec_map::VecMap;
+use glium::glutin::ElementState;
+use super::{KeyCode, KeyState, KeyStates, key_name};
+
+macro_rules! physical_keys {
+    ($($(#[$doc:meta])* $key:ident: $set1:expr, $evdev:expr, $mac:expr);*) => {
+        /// A key by its position on the keyboard, named after the key found
+        /// there on a US QWERTY keyboard. `PhysicalKey::W` is the key above
+        /// `S` no matter whether the layout puts a W, a Z or a comma there,
+        /// so WASD controls work for every player.
+        ///
+        /// This covers the main block of the keyboard, the function keys and
+        /// the arrow keys, but not the keypad or the keys around the arrows.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum PhysicalKey {
+            $($(#[$doc])* $key),*
+        }
+
+        /// Every physical key with its scancode in PC scancode set 1 as
+        /// Windows reports it, as a Linux evdev code and as a macOS virtual
+        /// key code
+        const PHYSICAL_KEYS: &'static [(PhysicalKey, &'static str, u8, u8, u8)] = &[
+            $((PhysicalKey::$key, stringify!($key), $set1, $evdev, $mac)),*
+        ];
+    }
+}
+
+physical_keys!(
+    /// The Escape key
+    Escape: 1, 1, 53;
+    /// The 1 key of the number row
+    Key1: 2, 2, 18;
+    /// The 2 key of the number row
+    Key2: 3, 3, 19;
+    /// The 3 key of the number row
+    Key3: 4, 4, 20;
+    /// The 4 key of the number row
+    Key4: 5, 5, 21;
+    /// The 5 key of the number row
+    Key5: 6, 6, 23;
+    /// The 6 key of the number row
+    Key6: 7, 7, 22;
+    /// The 7 key of the number row
+    Key7: 8, 8, 26;
+    /// The 8 key of the number row
+    Key8: 9, 9, 28;
+    /// The 9 key of the number row
+    Key9: 10, 10, 25;
+    /// The 0 key of the number row
+    Key0: 11, 11, 29;
+    /// The key right of 0, `-` on US layouts
+    Minus: 12, 12, 27;
+    /// The key left of Backspace, `=` on US layouts
+    Equals: 13, 13, 24;
+    /// The Backspace key
+    Back: 14, 14, 51;
+    /// The Tab key
+    Tab: 15, 15, 48;
+    /// The Q key
+    Q: 16, 16, 12;
+    /// The W key
+    W: 17, 17, 13;
+    /// The E key
+    E: 18, 18, 14;
+    /// The R key
+    R: 19, 19, 15;
+    /// The T key
+    T: 20, 20, 17;
+    /// The Y key
+    Y: 21, 21, 16;
+    /// The U key
+    U: 22, 22, 32;
+    /// The I key
+    I: 23, 23, 34;
+    /// The O key
+    O: 24, 24, 31;
+    /// The P key
+    P: 25, 25, 35;
+    /// The key right of P, `[` on US layouts
+    LBracket: 26, 26, 33;
+    /// The second key right of P, `]` on US layouts
+    RBracket: 27, 27, 30;
+    /// The Return key
+    Return: 28, 28, 36;
+    /// The left Control key
+    LControl: 29, 29, 59;
+    /// The A key
+    A: 30, 30, 0;
+    /// The S key
+    S: 31, 31, 1;
+    /// The D key
+    D: 32, 32, 2;
+    /// The F key
+    F: 33, 33, 3;
+    /// The G key
+    G: 34, 34, 5;
+    /// The H key
+    H: 35, 35, 4;
+    /// The J key
+    J: 36, 36, 38;
+    /// The K key
+    K: 37, 37, 40;
+    /// The L key
+    L: 38, 38, 37;
+    /// The key right of L, `;` on US layouts
+    Semicolon: 39, 39, 41;
+    /// The second key right of L, `'` on US layouts
+    Apostrophe: 40, 40, 39;
+    /// The key left of 1, `` ` `` on US layouts
+    Grave: 41, 41, 50;
+    /// The left Shift key
+    LShift: 42, 42, 56;
+    /// The key above Return, `\` on US layouts
+    Backslash: 43, 43, 42;
+    /// The Z key
+    Z: 44, 44, 6;
+    /// The X key
+    X: 45, 45, 7;
+    /// The C key
+    C: 46, 46, 8;
+    /// The V key
+    V: 47, 47, 9;
+    /// The B key
+    B: 48, 48, 11;
+    /// The N key
+    N: 49, 49, 45;
+    /// The M key
+    M: 50, 50, 46;
+    /// The key right of M, `,` on US layouts
+    Comma: 51, 51, 43;
+    /// The second key right of M, `.` on US layouts
+    Period: 52, 52, 47;
+    /// The third key right of M, `/` on US layouts
+    Slash: 53, 53, 44;
+    /// The right Shift key
+    RShift: 54, 54, 60;
+    /// The left Alt key, Option on a Mac
+    LAlt: 56, 56, 58;
+    /// The space bar
+    Space: 57, 57, 49;
+    /// The Caps Lock key
+    Capital: 58, 58, 57;
+    /// The F1 key
+    F1: 59, 59, 122;
+    /// The F2 key
+    F2: 60, 60, 120;
+    /// The F3 key
+    F3: 61, 61, 99;
+    /// The F4 key
+    F4: 62, 62, 118;
+    /// The F5 key
+    F5: 63, 63, 96;
+    /// The F6 key
+    F6: 64, 64, 97;
+    /// The F7 key
+    F7: 65, 65, 98;
+    /// The F8 key
+    F8: 66, 66, 100;
+    /// The F9 key
+    F9: 67, 67, 101;
+    /// The F10 key
+    F10: 68, 68, 109;
+    /// The F11 key
+    F11: 87, 87, 103;
+    /// The F12 key
+    F12: 88, 88, 111;
+    /// The up arrow key
+    Up: 72, 103, 126;
+    /// The left arrow key
+    Left: 75, 105, 123;
+    /// The right arrow key
+    Right: 77, 106, 124;
+    /// The down arrow key
+    Down: 80, 108, 125
+);
+
+/// Converts a scancode to what glutin reports on this platform, Windows
+/// reports scancode set 1 without the extended bit
+#[cfg(target_os = "windows")]
+fn native(set1: u8, _evdev: u8, _mac: u8) -> u8 {
+    set1
+}
+
+/// Converts a scancode to what glutin reports on this platform
+#[cfg(target_os = "macos")]
+fn native(_set1: u8, _evdev: u8, mac: u8) -> u8 {
+    mac
+}
+
+/// Converts a scancode to what glutin reports on this platform, X11 key
+/// codes are evdev codes offset by 8
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn native(_set1: u8, evdev: u8, _mac: u8) -> u8 {
+    evdev + 8
+}
+
+impl PhysicalKey {
+    /// The scancode glutin reports for this key on the current platform
+    pub fn scancode(&self) -> u8 {
+        let &(_, _, set1, evdev, mac) = self.entry();
+        native(set1, evdev, mac)
+    }
+
+    /// Looks up the physical key with the given scancode
+    pub fn from_scancode(scancode: u8) -> Option<PhysicalKey> {
+        PHYSICAL_KEYS.iter()
+            .find(|&&(_, _, set1, evdev, mac)| native(set1, evdev, mac) == scancode)
+            .map(|&(key, _, _, _, _)| key)
+    }
+
+    /// The name of the position, as used in config files
+    pub fn name(&self) -> &'static str {
+        self.entry().1
+    }
+
+    /// Looks up a physical key by its name
+    pub fn from_name(name: &str) -> Option<PhysicalKey> {
+        PHYSICAL_KEYS.iter().find(|&&(_, n, _, _, _)| n == name).map(|&(key, _, _, _, _)| key)
+    }
+
+    fn entry(&self) -> &'static (PhysicalKey, &'static str, u8, u8, u8) {
+        &PHYSICAL_KEYS[*self as usize]
+    }
+}
+
+/// The state of all keys by their scancode, kept alongside `Keys` and
+/// following the same sequence of states. Scancodes don't depend on the
+/// keyboard layout, see `PhysicalKey` for the ones of common keys.
+///
+/// The layout is learned from the key events: once a key has been pressed
+/// its meaning in the current layout is known, so menus can show the player
+/// what to press. Keys that have not been pressed yet are only known by
+/// their US QWERTY name, see `layout_name`.
+///
+/// Consuming a key in `Keys` consumes its scancode as well, once the layout
+/// knows which one it is.
+pub struct Scancodes {
+    states: KeyStates,
+    layout: VecMap<KeyCode>,
+    consumed: RefCell<Vec<u8>>,
+    all_consumed: Cell<bool>,
+}
+
+impl Scancodes {
+    /// Creates a new scancodes struct
+    pub fn new() -> Scancodes {
+        Scancodes {
+            states: KeyStates::new(),
+            layout: VecMap::new(),
+            consumed: RefCell::new(Vec::new()),
+            all_consumed: Cell::new(false),
+        }
+    }
+
+    /// Gives you the KeyState of a given scancode
+    pub fn status(&self, scancode: u8) -> KeyState {
+        match self.states.status(scancode as usize) {
+            KeyState::Pressed(t) | KeyState::Held(t) if self.is_consumed(scancode) =>
+                KeyState::NotPressed(t),
+            state => state
+        }
+    }
+
+    /// Marks a scancode as handled, it reads as not pressed until the next
+    /// tick
+    pub fn consume(&self, scancode: u8) {
+        self.consumed.borrow_mut().push(scancode);
+    }
+
+    /// Consumes every scancode the current layout maps to `key`
+    pub fn consume_key(&self, key: KeyCode) {
+        for (scancode, &k) in self.layout.iter() {
+            if k == key {
+                self.consume(scancode as u8);
+            }
+        }
+    }
+
+    /// Marks all scancodes as handled until the next tick
+    pub fn consume_all(&self) {
+        self.all_consumed.set(true);
+    }
+
+    /// Whether a scancode has been consumed during this tick
+    pub fn is_consumed(&self, scancode: u8) -> bool {
+        self.all_consumed.get() || self.consumed.borrow().contains(&scancode)
+    }
+
+    /// A quick way to check if a given scancode is pressed or held
+    pub fn held(&self, scancode: u8) -> bool {
+        match self.status(scancode) {
+            KeyState::Pressed(_)  | KeyState::Held(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given scancode has just been pressed
+    pub fn pressed(&self, scancode: u8) -> bool {
+        match self.status(scancode) {
+            KeyState::Pressed(_) => { true },
+            KeyState::Released(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// A quick way to check if a given scancode has just been released
+    pub fn released(&self, scancode: u8) -> bool {
+        match self.status(scancode) {
+            KeyState::Released(_) => { true },
+            KeyState::Pressed(_) | KeyState::NotPressed(_)
+                | KeyState::Held(_) => { false }
+        }
+    }
+
+    /// The KeyState of the key at the given position
+    pub fn physical(&self, key: PhysicalKey) -> KeyState {
+        self.status(key.scancode())
+    }
+
+    /// What the current layout makes of the key at the given position, this
+    /// is only known once it has been pressed
+    pub fn layout_key(&self, key: PhysicalKey) -> Option<KeyCode> {
+        self.layout.get(&(key.scancode() as usize)).cloned()
+    }
+
+    /// The name of the key at the given position in the current layout, for
+    /// display in menus. Falls back to the US QWERTY name as long as the
+    /// layout is not known.
+    ///
+    /// glutin can not be asked for the layout, so it is only learned from
+    /// keys that have been pressed during this run. Until then a remapping
+    /// menu shows QWERTY names even to players with another layout, so ask
+    /// them to press the key instead of relying on this alone.
+    pub fn layout_name(&self, key: PhysicalKey) -> &'static str {
+        self.layout_key(key).and_then(key_name).unwrap_or(key.name())
+    }
+
+    /// Update a given scancode with a given state, `key` is what the current
+    /// layout makes of it
+    pub fn update(&mut self, scancode: u8, key: Option<KeyCode>,
+                  state: ElementState, time: f64) {
+        if let Some(key) = key {
+            self.layout.insert(scancode as usize, key);
+        }
+        self.states.update(scancode as usize, state, time);
+    }
+
+    /// Releases all keys that are held down
+    pub fn release_all(&mut self, time: f64) {
+        self.states.release_all(time);
+    }
+
+    /// Advance all scancodes to the next step
+    pub fn next(&mut self) {
+        self.states.next();
+        self.consumed.borrow_mut().clear();
+        self.all_consumed.set(false);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glium::glutin::ElementState::{Pressed, Released};
+    use event::KeyCode;
+
+    #[test]
+    fn physical_keys() {
+        for &(key, name, _, _, _) in super::PHYSICAL_KEYS {
+            assert_eq!(PhysicalKey::from_name(name), Some(key));
+            assert_eq!(PhysicalKey::from_scancode(key.scancode()), Some(key));
+            assert_eq!(format!("{:?}", key), name);
+        }
+    }
+
+    #[test]
+    fn azerty_layout() {
+        let mut codes = Scancodes::new();
+        let w = PhysicalKey::W.scancode();
+        assert_eq!(codes.layout_name(PhysicalKey::W), "W");
+
+        codes.update(w, Some(KeyCode::Z), Pressed, 1.0);
+        assert!(codes.pressed(w));
+        codes.consume_key(KeyCode::Z);
+        assert!(!codes.held(w));
+        assert_eq!(codes.layout_key(PhysicalKey::W), Some(KeyCode::Z));
+        assert_eq!(codes.layout_name(PhysicalKey::W), "Z");
+
+        codes.next();
+        codes.update(w, None, Released, 2.0);
+        assert!(codes.released(w));
+        assert_eq!(codes.layout_name(PhysicalKey::W), "Z");
+    }
+}
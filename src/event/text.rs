@@ -0,0 +1,303 @@
+use std::cell::Cell;
+use super::KeyCode;
+
+/// A single change to a piece of text, in the order the player made them
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextEdit {
+    /// A character has been typed
+    Insert(char),
+    /// Removes the character before the cursor
+    Backspace,
+    /// Removes the character after the cursor
+    Delete,
+    /// Moves the cursor one character to the left
+    Left,
+    /// Moves the cursor one character to the right
+    Right,
+    /// Moves the cursor to the start of the text
+    Home,
+    /// Moves the cursor to the end of the text
+    End,
+}
+
+impl TextEdit {
+    /// The edit a key stands for, if any
+    fn from_key(key: KeyCode) -> Option<TextEdit> {
+        match key {
+            KeyCode::Back => Some(TextEdit::Backspace),
+            KeyCode::Delete => Some(TextEdit::Delete),
+            KeyCode::Left => Some(TextEdit::Left),
+            KeyCode::Right => Some(TextEdit::Right),
+            KeyCode::Home => Some(TextEdit::Home),
+            KeyCode::End => Some(TextEdit::End),
+            _ => None
+        }
+    }
+}
+
+/// The text the player typed during a single tick.
+///
+/// Text input is off by default, turn it on with `start` while a text field
+/// has focus. Characters come from the window system, so they respect the
+/// keyboard layout and input methods of the player, unlike `KeyCode`s.
+/// Editing keys are repeated as long as they are held down.
+///
+/// A text field that took the text can `consume` it, so scenes lower on the
+/// stack don't get it typed in as well.
+pub struct TextInput {
+    active: Cell<bool>,
+    consumed: Cell<bool>,
+    edits: Vec<TextEdit>,
+}
+
+impl TextInput {
+    /// Creates a new, inactive text input
+    pub fn new() -> TextInput {
+        TextInput {
+            active: Cell::new(false),
+            consumed: Cell::new(false),
+            edits: Vec::new(),
+        }
+    }
+
+    /// Starts collecting typed text, beginning with the next tick
+    pub fn start(&self) {
+        self.active.set(true);
+    }
+
+    /// Stops collecting typed text
+    pub fn stop(&self) {
+        self.active.set(false);
+    }
+
+    /// Whether typed text is being collected
+    pub fn active(&self) -> bool {
+        self.active.get()
+    }
+
+    /// Marks the text of this tick as handled, there are no edits until the
+    /// next tick
+    pub fn consume(&self) {
+        self.consumed.set(true);
+    }
+
+    /// Whether the text has been consumed during this tick
+    pub fn is_consumed(&self) -> bool {
+        self.consumed.get()
+    }
+
+    /// All edits of this tick
+    pub fn edits(&self) -> &[TextEdit] {
+        if self.consumed.get() { &self.edits[..0] } else { &self.edits[..] }
+    }
+
+    /// The characters typed this tick, ignoring all other edits
+    pub fn typed(&self) -> String {
+        self.edits().iter().filter_map(|edit| match *edit {
+            TextEdit::Insert(c) => Some(c),
+            _ => None
+        }).collect()
+    }
+
+    /// Records a typed character. Control characters are dropped, the
+    /// editing keys are picked up through `key` instead.
+    pub fn character(&mut self, c: char) {
+        if self.active() && !c.is_control() {
+            self.edits.push(TextEdit::Insert(c));
+        }
+    }
+
+    /// Records a key press, including repeated ones
+    pub fn key(&mut self, key: KeyCode) {
+        if !self.active() {
+            return;
+        }
+        if let Some(edit) = TextEdit::from_key(key) {
+            self.edits.push(edit);
+        }
+    }
+
+    /// Forgets the edits of the last tick
+    pub fn update(&mut self) {
+        self.edits.clear();
+        self.consumed.set(false);
+    }
+}
+
+/// A single line of editable text with a cursor, feed it the `TextInput`
+/// each tick while it has focus.
+#[derive(Clone, Debug)]
+pub struct TextField {
+    value: String,
+    cursor: usize,
+    max_len: Option<usize>,
+}
+
+impl TextField {
+    /// Creates an empty text field
+    pub fn new() -> TextField {
+        TextField {
+            value: String::new(),
+            cursor: 0,
+            max_len: None,
+        }
+    }
+
+    /// Limits the field to `max_len` characters
+    pub fn with_max_len(mut self, max_len: usize) -> TextField {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// The current text
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The position of the cursor in characters
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replaces the text and moves the cursor to its end
+    pub fn set_value(&mut self, value: &str) {
+        self.value = match self.max_len {
+            Some(max) => value.chars().take(max).collect(),
+            None => value.to_string()
+        };
+        self.cursor = self.len();
+    }
+
+    /// Removes all text
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    /// Applies the edits of this tick, returns whether the text changed
+    pub fn apply(&mut self, text: &TextInput) -> bool {
+        let mut changed = false;
+        for edit in text.edits() {
+            if self.edit(*edit) {
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Applies a single edit, returns whether the text changed
+    pub fn edit(&mut self, edit: TextEdit) -> bool {
+        let len = self.len();
+        match edit {
+            TextEdit::Insert(c) => {
+                if self.max_len.map_or(false, |max| len >= max) {
+                    return false;
+                }
+                let at = self.byte_offset(self.cursor);
+                self.value.insert(at, c);
+                self.cursor += 1;
+                true
+            },
+            TextEdit::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_offset(self.cursor);
+                self.value.remove(at);
+                true
+            },
+            TextEdit::Delete if self.cursor < len => {
+                let at = self.byte_offset(self.cursor);
+                self.value.remove(at);
+                true
+            },
+            TextEdit::Left if self.cursor > 0 => {
+                self.cursor -= 1;
+                false
+            },
+            TextEdit::Right if self.cursor < len => {
+                self.cursor += 1;
+                false
+            },
+            TextEdit::Home => {
+                self.cursor = 0;
+                false
+            },
+            TextEdit::End => {
+                self.cursor = len;
+                false
+            },
+            _ => false
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn byte_offset(&self, chars: usize) -> usize {
+        self.value.char_indices().nth(chars).map_or(self.value.len(), |(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use event::KeyCode;
+
+    #[test]
+    fn inactive_ignores_input() {
+        let mut text = TextInput::new();
+        text.character('a');
+        text.key(KeyCode::Back);
+        assert!(text.edits().is_empty());
+
+        text.start();
+        text.character('a');
+        text.character('\u{8}');
+        text.key(KeyCode::Back);
+        text.key(KeyCode::A);
+        assert_eq!(text.edits(), &[TextEdit::Insert('a'), TextEdit::Backspace]);
+        assert_eq!(text.typed(), "a");
+
+        text.update();
+        assert!(text.edits().is_empty());
+        assert!(text.active());
+    }
+
+    #[test]
+    fn field_editing() {
+        let mut text = TextInput::new();
+        text.start();
+        for c in "hëllo".chars() {
+            text.character(c);
+        }
+        text.key(KeyCode::Left);
+        text.key(KeyCode::Left);
+        text.key(KeyCode::Back);
+        text.key(KeyCode::Delete);
+        text.character('y');
+
+        let mut field = TextField::new();
+        assert!(field.apply(&text));
+        assert_eq!(field.value(), "hëyo");
+        assert_eq!(field.cursor(), 3);
+
+        assert!(!field.edit(TextEdit::Home));
+        assert!(!field.edit(TextEdit::Backspace));
+        assert!(!field.edit(TextEdit::Left));
+        assert_eq!(field.cursor(), 0);
+        assert!(!field.edit(TextEdit::End));
+        assert!(!field.edit(TextEdit::Delete));
+        assert_eq!(field.cursor(), 4);
+    }
+
+    #[test]
+    fn field_max_len() {
+        let mut field = TextField::new().with_max_len(3);
+        field.set_value("abcdef");
+        assert_eq!(field.value(), "abc");
+        assert!(!field.edit(TextEdit::Insert('d')));
+        assert!(field.edit(TextEdit::Backspace));
+        assert!(field.edit(TextEdit::Insert('x')));
+        assert_eq!(field.value(), "abx");
+    }
+}
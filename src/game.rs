@@ -1,9 +1,155 @@
 use glium::backend::glutin_backend::GlutinFacade;
 use scene::SceneManager;
-use event::step::fixed_60;
+use event::step::{accumulated, variable, CatchUp, Lag};
+use event::StepResult;
 use scene::Scene;
-use event::Keys;
-use time;
+use event::{Input, InputEvent, TimedEvent, KeyCode, ActionMap, PhysicalKey, key_name};
+use event::gamepad::{GamepadBackend, NoGamepads, VirtualGamepads};
+use event::record::{Frame, RecordedEvent, Recording};
+use clock::{Clock, MonotonicClock, SimulatedClock, TimeControl};
+use stats::{self, Stats};
+use std::rc::Rc;
+use std::mem;
+use glium::glutin::{Event, ElementState};
+
+/// Decides how `Game::kickoff` paces updating and drawing
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LoopMode {
+    /// Update the scenes the given amount of times per second with a constant
+    /// `dt`, and draw once in between. This is the default, with 60 updates.
+    Fixed(u32),
+    /// Update once for every presented frame with the time that passed in
+    /// between. The display has to be created with vsync enabled, as drawing
+    /// is what keeps this mode from spinning. `GameBuilder` does so for this
+    /// mode, without vsync this updates and draws as fast as possible.
+    VSync,
+    /// Update and draw as fast as possible with the time that passed in
+    /// between, useful for benchmarking. `GameBuilder` turns vsync off for
+    /// this mode.
+    Uncapped,
+    /// Sleep until some input arrives, then update with the time that passed
+    /// since the last update and draw once. Meant for turn based games and
+    /// tools that should not burn CPU when nothing happens.
+    ///
+    /// Only window events wake the game up. Gamepads are polled and timers
+    /// advanced in the update that follows one, so in this mode controllers
+    /// do nothing on their own and timers only fire once the window gets
+    /// some input, like a mouse move.
+    EventDriven,
+}
+
+/// What `Game` does while its window is in the background, that is while it
+/// is unfocused or minimized
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BackgroundPolicy {
+    /// Keep updating and drawing as usual, this is the default
+    Run,
+    /// Stop updating the scenes and draw at most once every `render_interval`
+    /// seconds, nothing is drawn while minimized. Input is still handled, so
+    /// the game notices when it comes back to the foreground.
+    ///
+    /// The scenes lose the input of the skipped ticks: presses, typed text
+    /// and the event queue are gone by the time they are updated again. The
+    /// window hardly gets any input while in the background anyway, and
+    /// resizes, focus changes and dropped files still reach the scenes
+    /// through their hooks.
+    Pause {
+        /// The least amount of seconds between two frames
+        render_interval: f64
+    },
+}
+
+/// The most updates per second a `LoopMode::Fixed` may ask for, so that a
+/// step still lasts at least a nanosecond
+pub const MAX_TICK_RATE: u32 = 1000_000_000;
+
+/// Debug key bindings driving the `TimeControl` of a `Game`
+#[derive(Copy, Clone, Debug)]
+pub struct TimeKeys {
+    /// Pauses or resumes the simulation
+    pub pause: KeyCode,
+    /// Advances a paused simulation by one tick
+    pub step: KeyCode,
+    /// Halves the time scale
+    pub slower: KeyCode,
+    /// Doubles the time scale
+    pub faster: KeyCode,
+}
+
+/// A scripted sequence of window events for `Game::run_headless`
+pub struct Script {
+    events: Vec<(usize, Event)>
+}
+
+impl Script {
+    /// Creates an empty script
+    pub fn new() -> Script {
+        Script {
+            events: Vec::new()
+        }
+    }
+
+    /// Delivers `ev` right before the given tick, counting from 0. Events
+    /// for the same tick are delivered in the order they were added.
+    pub fn at(mut self, tick: usize, ev: Event) -> Script {
+        self.events.push((tick, ev));
+        self
+    }
+
+    /// Presses `key` right before the given tick, its scancode is the one of
+    /// the key on a US layout
+    pub fn press(self, tick: usize, key: KeyCode) -> Script {
+        self.at(tick, key_event(ElementState::Pressed, key))
+    }
+
+    /// Releases `key` right before the given tick
+    pub fn release(self, tick: usize, key: KeyCode) -> Script {
+        self.at(tick, key_event(ElementState::Released, key))
+    }
+
+    /// Types `text` right before the given tick
+    pub fn type_text(self, tick: usize, text: &str) -> Script {
+        text.chars().fold(self, |script, c| script.at(tick, Event::ReceivedCharacter(c)))
+    }
+
+    /// Closes the window right before the given tick
+    pub fn close(self, tick: usize) -> Script {
+        self.at(tick, Event::Closed)
+    }
+
+    /// Takes out all events for the given tick
+    fn take(&mut self, tick: usize) -> Vec<Event> {
+        let (now, later): (Vec<(usize, Event)>, Vec<(usize, Event)>) =
+            mem::replace(&mut self.events, Vec::new())
+                .into_iter().partition(|&(t, _)| t == tick);
+        self.events = later;
+        now.into_iter().map(|(_, ev)| ev).collect()
+    }
+}
+
+/// The scancode scripted keys without a `PhysicalKey` get, no platform uses
+/// it for any of them so `Scancodes` never takes it for another key
+const UNKNOWN_SCANCODE: u8 = 0xff;
+
+/// The scancode of a key on a US layout, if it is a `PhysicalKey`
+fn us_scancode(key: KeyCode) -> Option<u8> {
+    key_name(key).and_then(PhysicalKey::from_name).map(|p| p.scancode())
+}
+
+/// A scripted key event, with the scancode of the key on a US layout
+fn key_event(state: ElementState, key: KeyCode) -> Event {
+    Event::KeyboardInput(state, us_scancode(key).unwrap_or(UNKNOWN_SCANCODE), Some(key))
+}
+
+/// What is left of a `Game` after `Game::run_headless` returns
+pub struct HeadlessRun<T, M> {
+    /// Your own state
+    pub state: T,
+    /// The scene manager, with all scenes that were still on the stack
+    pub scene_mgr: M,
+    /// How many ticks were run
+    pub ticks: usize,
+}
 
 /// The game object, you give it your initial State and start it off
 pub struct Game<T, M> where M: SceneManager<T> {
@@ -13,10 +159,43 @@ pub struct Game<T, M> where M: SceneManager<T> {
     scene_mgr: M,
     /// The display handle
     display: GlutinFacade,
-    /// KeyStates
-    keys: Keys,
-    /// Time started
-    time_started: f64,
+    /// Keyboard, mouse and gamepad state
+    input: Input,
+    /// Where gamepad events come from
+    gamepad_backend: Box<GamepadBackend>,
+    /// Where the time comes from
+    clock: Rc<Clock>,
+    /// How the loop is paced
+    loop_mode: LoopMode,
+    /// How much the loop may catch up after slow frames
+    catch_up: CatchUp,
+    /// Window events that have been received but not yet handled, with the
+    /// time they were received at
+    pending: Vec<(f64, Event)>,
+    /// Scaling and pausing of the simulation
+    time: TimeControl,
+    /// Optional debug keys for the time control
+    time_keys: Option<TimeKeys>,
+    /// Whether the window should be ignored, see `run_headless`
+    headless: bool,
+    /// Whether the window has the focus
+    focused: bool,
+    /// Whether the window is minimized
+    minimized: bool,
+    /// What to do while the window is unfocused or minimized
+    background: BackgroundPolicy,
+    /// When the scenes were last drawn
+    last_render: f64,
+    /// Where the input of every tick is recorded to, if anywhere
+    recording: Option<Recording>,
+    /// Called with your state once the game ends
+    shutdown_hooks: Vec<Box<FnMut(&mut T)>>,
+    /// Frame timing statistics, also holds the time we started at
+    stats: Stats,
+    /// Called every so often with the statistics
+    reporter: Option<(f64, Box<FnMut(&Stats)>)>,
+    /// When the reporter was last called
+    last_report: f64,
     /// Time now
     time_now: f64,
 }
@@ -24,44 +203,811 @@ pub struct Game<T, M> where M: SceneManager<T> {
 impl<T, M> Game<T, M> where M: SceneManager<T> {
     /// Creates a new game you can start!
     pub fn new(state: T, mgr: M, disp: GlutinFacade) -> Game<T, M> {
+        Game::with_clock(state, mgr, disp, Rc::new(MonotonicClock))
+    }
+
+    /// Creates a new game that takes its time from the given clock, use a
+    /// `clock::SimulatedClock` to get reproducible timings.
+    pub fn with_clock(state: T, mgr: M, disp: GlutinFacade, clock: Rc<Clock>)
+        -> Game<T, M>
+    {
+        let mut input = Input::new();
+        let (width, height) = disp.get_framebuffer_dimensions();
+        input.mouse.set_window_size(width, height);
+
         Game {
             state: state,
             scene_mgr: mgr,
             display: disp,
-            keys: Keys::new(),
-            time_started: 0.0,
+            input: input,
+            gamepad_backend: Box::new(NoGamepads),
+            clock: clock,
+            loop_mode: LoopMode::Fixed(60),
+            catch_up: CatchUp::default(),
+            pending: Vec::new(),
+            time: TimeControl::new(),
+            time_keys: None,
+            headless: false,
+            focused: true,
+            minimized: false,
+            background: BackgroundPolicy::Run,
+            last_render: 0.0,
+            recording: None,
+            shutdown_hooks: Vec::new(),
+            stats: Stats::new(stats::DEFAULT_WINDOW),
+            reporter: None,
+            last_report: 0.0,
             time_now: 0.0
         }
     }
 
+    /// Sets where gamepad events come from, it is polled once per tick. Per
+    /// default there are no gamepads, a `gamepad::VirtualGamepads` can be
+    /// used to test without any controller.
+    pub fn set_gamepad_backend(&mut self, backend: Box<GamepadBackend>) {
+        self.gamepad_backend = backend;
+    }
+
+    /// Gives you a handle to the action bindings of this game. Bind your
+    /// actions before starting the game, and put the handle into your state
+    /// to change them from within a scene.
+    pub fn actions(&self) -> ActionMap {
+        self.input.actions.map().clone()
+    }
+
+    /// Sets what the game does while its window is unfocused or minimized
+    pub fn set_background_policy(&mut self, policy: BackgroundPolicy) {
+        self.background = policy;
+    }
+
+    /// Starts recording the input of every tick, together with its time and
+    /// step. Save the returned handle once the game is over and pass it to
+    /// `replay` to run the same session again.
+    pub fn record(&mut self) -> Recording {
+        let recording = Recording::new();
+        self.recording = Some(recording.clone());
+        recording
+    }
+
+    /// Changes how the game loop is paced, see `LoopMode`.
+    ///
+    /// **This panics if a `LoopMode::Fixed` with a rate of 0 or above
+    /// `MAX_TICK_RATE` is given.**
+    pub fn set_loop_mode(&mut self, mode: LoopMode) {
+        if let LoopMode::Fixed(rate) = mode {
+            if rate == 0 || rate > MAX_TICK_RATE {
+                panic!("Tried to set a fixed loop mode with {} ticks per second.", rate);
+            }
+        }
+        self.loop_mode = mode;
+    }
+
+    /// The current `LoopMode`
+    pub fn loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
+    /// Limits how much the game loop catches up after frames that took too
+    /// long, see `event::step::CatchUp`. Whenever the simulation falls behind
+    /// it is recorded in the `Stats`.
+    pub fn set_catch_up(&mut self, catch_up: CatchUp) {
+        self.catch_up = catch_up;
+    }
+
+    /// Gives you a handle to control the passing of time in this game, put it
+    /// into your state to pause or slow down the game from within your
+    /// scenes. Scenes are still drawn while paused, but neither `keypress`
+    /// nor `tick` are called.
+    pub fn time_control(&self) -> TimeControl {
+        self.time.clone()
+    }
+
+    /// Lets the given keys drive the time control, handy while debugging.
+    /// These keys are still passed on to the scenes as well.
+    pub fn bind_time_keys(&mut self, keys: TimeKeys) {
+        self.time_keys = Some(keys);
+    }
+
+    /// Calls `hook` with your state once the game ends, after all remaining
+    /// scenes have been left. Hooks are called in the order they were added.
+    pub fn on_shutdown(&mut self, hook: Box<FnMut(&mut T)>) {
+        self.shutdown_hooks.push(hook);
+    }
+
+    /// Gives you a handle to the frame timing statistics of this game. Put
+    /// it into your state to query it from within your scenes.
+    pub fn stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
+    /// Calls `cb` with the statistics every `interval` seconds while the game
+    /// is running, for example to print the current FPS.
+    pub fn report_every(&mut self, interval: f64, cb: Box<FnMut(&Stats)>) {
+        self.reporter = Some((interval, cb));
+    }
+
     /// Consumes the game and starts the display loop, once there are no
     /// more scenes or the window is closed this method returns.
+    ///
+    /// Closing the window first asks the top scene through
+    /// `Scene::close_requested`, which may veto it. Once the loop ends all
+    /// remaining scenes are left from the top down and dropped, then the
+    /// shutdown hooks are run.
+    ///
+    /// By default the scenes are updated 60 times a second with a constant
+    /// `dt`, and drawn once per loop iteration, see `event::step::accumulated`.
+    /// This can be changed with `set_loop_mode`.
     pub fn kickoff(mut self) {
-        self.time_started = time::precise_time_ns() as f64 / 1000_000_000. as f64;
-        fixed_60(|dt| {
-            use glium::glutin::Event;
-            use event::StepResult;
-            self.time_now = time::precise_time_ns() as f64 / 1000_000_000. as f64;
+        let clock = self.clock.clone();
+        self.time_now = clock.now();
+        self.last_report = self.time_now;
+        self.stats.start(self.time_now);
 
-            self.keys.update(self.time_now);
+        let mode = self.loop_mode;
+        let catch_up = self.catch_up;
+        match mode {
+            LoopMode::Fixed(rate) => {
+                accumulated(&*clock, 1000_000_000 / rate as u64, catch_up, &mut self,
+                            |game, dt| game.tick(dt),
+                            |game, alpha| game.render(alpha),
+                            |game, lag| game.lagged(lag));
+            },
+            LoopMode::VSync | LoopMode::Uncapped => {
+                variable(&*clock, catch_up, &mut self,
+                         |game, dt| game.tick(dt),
+                         |game| game.render(0.0),
+                         |game, lag| game.lagged(lag));
+            },
+            LoopMode::EventDriven => {
+                let mut last = clock.now_ns();
+                loop {
+                    if let Some(ev) = self.display.wait_events().next() {
+                        self.pending.push((clock.now(), ev));
+                    }
 
-            for ev in self.display.poll_events() {
-                match ev {
-                    Event::Closed => return StepResult::Stop,
-                    Event::KeyboardInput(state, _, Some(key)) => {
-                        self.keys.update_key(key, state, self.time_now);
+                    let now = clock.now_ns();
+                    // Waiting for input is not falling behind, so nothing is
+                    // recorded when clamping here
+                    let (dt, _) = catch_up.clamp(now - last);
+                    if let StepResult::Stop = self.tick(dt as f64 / 1000_000_000. as f64) {
+                        break;
+                    }
+                    last = now;
+
+                    if let StepResult::Stop = self.render(0.0) {
+                        break;
                     }
-                    _ => ()
                 }
             }
+        }
+
+        self.shutdown();
+    }
+
+    /// Consumes the game and runs it for `ticks` ticks without looking at the
+    /// window, or until there are no more scenes or a close event was
+    /// scripted. Meant for integration tests, usually with a display built
+    /// from a `HeadlessRendererBuilder`.
+    ///
+    /// Instead of real window events the events of `script` are fed to the
+    /// game. Time is simulated: it starts at 0 and every tick advances it by
+    /// exactly one step of the `LoopMode::Fixed` rate (60 per second for the
+    /// other modes), so this runs as fast as possible. Scenes are drawn once
+    /// after every tick. Just like with `kickoff` the remaining scenes are left
+    /// and the shutdown hooks are run at the end.
+    pub fn run_headless(mut self, ticks: usize, mut script: Script) -> HeadlessRun<T, M> {
+        let step = match self.loop_mode {
+            LoopMode::Fixed(rate) => 1000_000_000 / rate as u64,
+            _ => 16_666_667
+        };
+        let clock = SimulatedClock::new();
+        self.clock = Rc::new(clock.clone());
+        self.headless = true;
+        self.time_now = 0.0;
+        self.last_report = 0.0;
+        self.stats.start(0.0);
+
+        let mut ran = 0;
+        while ran < ticks {
+            clock.advance(step);
+            let now = clock.now();
+            self.pending.extend(script.take(ran).into_iter().map(|ev| (now, ev)));
+            ran += 1;
+
+            if let StepResult::Stop = self.tick(step as f64 / 1000_000_000. as f64) {
+                break;
+            }
+            if let StepResult::Stop = self.render(0.0) {
+                break;
+            }
+        }
+
+        self.shutdown();
+
+        HeadlessRun {
+            state: self.state,
+            scene_mgr: self.scene_mgr,
+            ticks: ran,
+        }
+    }
+
+    /// Consumes the game and runs it once for every tick of `recording`,
+    /// feeding it the recorded input instead of window and gamepad events.
+    /// Like with `run_headless` the clock is simulated, it is set to the
+    /// recorded time before each tick, and each tick advances by the
+    /// recorded step. As long as your scenes only depend on their input and
+    /// the time they are given, this plays out exactly like the recorded
+    /// session did.
+    pub fn replay(mut self, recording: &Recording) -> HeadlessRun<T, M> {
+        let clock = SimulatedClock::new();
+        let pads = VirtualGamepads::new();
+        self.clock = Rc::new(clock.clone());
+        self.gamepad_backend = Box::new(pads.clone());
+        self.headless = true;
+        self.time_now = 0.0;
+        self.last_report = 0.0;
+        self.stats.start(0.0);
+
+        let mut ran = 0;
+        for frame in recording.frames() {
+            let now = clock.now_ns();
+            if frame.time > now {
+                clock.advance(frame.time - now);
+            }
+            let now = clock.now();
+            for ev in frame.events {
+                match ev {
+                    RecordedEvent::Gamepad(pad) => pads.send(pad),
+                    ev => self.pending.extend(ev.to_event().map(|ev| (now, ev))),
+                }
+            }
+            ran += 1;
 
-            self.scene_mgr.update(dt, &self.keys);
-            if self.scene_mgr.get_scenes().len() == 0 {
-                return StepResult::Stop;
+            if let StepResult::Stop = self.tick(frame.dt) {
+                break;
+            }
+            if let StepResult::Stop = self.render(0.0) {
+                break;
             }
+        }
+
+        self.shutdown();
 
-            self.scene_mgr.display(&self.display);
+        HeadlessRun {
+            state: self.state,
+            scene_mgr: self.scene_mgr,
+            ticks: ran,
+        }
+    }
+
+    /// Polls the window and advances the scenes by one step of `dt`
+    fn tick(&mut self, dt: f64) -> StepResult {
+        // The recording gets the very same time, so a replay stamps the input
+        // exactly like this tick does
+        let now_ns = self.clock.now_ns();
+        self.time_now = now_ns as f64 / 1000_000_000. as f64;
+
+        self.input.update(self.time_now);
+
+        let mut events = mem::replace(&mut self.pending, Vec::new());
+        if !self.headless {
+            for ev in self.display.poll_events() {
+                events.push((self.clock.now(), ev));
+            }
+        }
+        let pad_time = self.clock.now();
+        let pad_events = self.gamepad_backend.poll();
+
+        if let Some(ref recording) = self.recording {
+            let mut recorded: Vec<RecordedEvent> = events.iter()
+                .filter_map(|&(_, ref ev)| RecordedEvent::from_event(ev)).collect();
+            recorded.extend(pad_events.iter().map(|&ev| RecordedEvent::Gamepad(ev)));
+            recording.push(Frame {
+                time: now_ns,
+                dt: dt,
+                events: recorded,
+            });
+        }
+
+        for (time, ev) in events {
+            match ev {
+                Event::Closed => {
+                    if self.scene_mgr.close_requested() {
+                        return StepResult::Stop;
+                    }
+                },
+                Event::KeyboardInput(state, scancode, key) => {
+                    self.input.keys.update_scancode(scancode, key, state, self.time_now);
+                    if let Some(key) = key {
+                        self.input.keys.update_key(key, state, self.time_now);
+                    }
+                },
+                Event::ReceivedCharacter(c) => {
+                    self.input.keys.update_char(c);
+                },
+                Event::MouseMoved((x, y)) => {
+                    self.input.mouse.update_position(x, y);
+                },
+                Event::MouseInput(state, button) => {
+                    self.input.mouse.update_button(button, state, self.time_now);
+                },
+                Event::MouseWheel(delta) => {
+                    self.input.mouse.update_wheel(delta);
+                },
+                Event::Resized(width, height) => {
+                    self.input.mouse.set_window_size(width, height);
+                    // Some platforms report minimizing as a resize to nothing
+                    if width == 0 || height == 0 {
+                        self.set_minimized(true);
+                    } else {
+                        self.set_minimized(false);
+                        self.scene_mgr.resized(width, height);
+                    }
+                },
+                Event::Focused(focused) => {
+                    if !focused {
+                        self.input.release_all(self.time_now);
+                    }
+                    if self.focused != focused {
+                        self.focused = focused;
+                        self.scene_mgr.focused(focused);
+                    }
+                },
+                Event::Suspended(suspended) => {
+                    self.set_minimized(suspended);
+                },
+                Event::DroppedFile(ref path) => {
+                    self.scene_mgr.file_dropped(path);
+                },
+                _ => ()
+            }
+            self.input.events.push(TimedEvent {
+                time: time,
+                event: InputEvent::Window(ev),
+            });
+        }
+
+        for ev in pad_events {
+            self.input.gamepads.handle(ev, self.time_now);
+            self.input.events.push(TimedEvent {
+                time: pad_time,
+                event: InputEvent::Gamepad(ev),
+            });
+        }
+        let modifiers = self.input.keys.modifiers();
+        self.input.mouse.set_modifiers(modifiers);
+        self.input.update_actions(self.time_now);
+
+        self.handle_time_keys();
+        if self.paused_in_background() {
             return StepResult::Continue;
-        });
+        }
+        let dt = match self.time.apply(dt) {
+            Some(dt) => dt,
+            None => return StepResult::Continue
+        };
+        self.input.update_axes(dt);
+
+        let start = self.clock.now_ns();
+        self.scene_mgr.update(dt, &self.input);
+        let took = self.clock.now_ns() - start;
+        self.stats.record_update(self.time_now, took as f64 / 1000_000_000. as f64);
+
+        if self.scene_mgr.get_scenes().len() == 0 {
+            return StepResult::Stop;
+        }
+
+        return StepResult::Continue;
     }
+
+    /// Records that the simulation fell behind
+    fn lagged(&mut self, lag: Lag) {
+        self.stats.record_lag(self.clock.now(), lag.dropped as f64 / 1000_000_000. as f64);
+    }
+
+    /// Leaves all remaining scenes and runs the shutdown hooks
+    fn shutdown(&mut self) {
+        self.scene_mgr.shutdown();
+        for hook in self.shutdown_hooks.iter_mut() {
+            hook(&mut self.state);
+        }
+    }
+
+    /// Applies the time keys, if any are bound
+    fn handle_time_keys(&mut self) {
+        if let Some(bindings) = self.time_keys {
+            if self.input.keys.pressed(bindings.pause) {
+                self.time.toggle_pause();
+            }
+            if self.input.keys.pressed(bindings.step) {
+                self.time.step();
+            }
+            if self.input.keys.pressed(bindings.slower) {
+                self.time.set_scale(self.time.scale() / 2.0);
+            }
+            if self.input.keys.pressed(bindings.faster) {
+                self.time.set_scale(self.time.scale() * 2.0);
+            }
+        }
+    }
+
+    /// Draws the scenes, `alpha` being how far we are into the next step
+    fn render(&mut self, alpha: f64) -> StepResult {
+        if let BackgroundPolicy::Pause { render_interval } = self.background {
+            if self.minimized ||
+                (!self.focused && self.time_now - self.last_render < render_interval) {
+                return StepResult::Continue;
+            }
+        }
+        self.last_render = self.time_now;
+
+        let start = self.clock.now_ns();
+        self.scene_mgr.display(&self.display, alpha);
+        let took = self.clock.now_ns() - start;
+        self.stats.record_display(start as f64 / 1000_000_000. as f64,
+                                  took as f64 / 1000_000_000. as f64);
+
+        self.report();
+        return StepResult::Continue;
+    }
+
+    /// Whether the scenes should not be updated because the window is in the
+    /// background
+    fn paused_in_background(&self) -> bool {
+        match self.background {
+            BackgroundPolicy::Run => false,
+            BackgroundPolicy::Pause { .. } => self.minimized || !self.focused
+        }
+    }
+
+    /// Notes that the window has been minimized or restored
+    fn set_minimized(&mut self, minimized: bool) {
+        if self.minimized != minimized {
+            self.minimized = minimized;
+            self.scene_mgr.minimized(minimized);
+        }
+    }
+
+    /// Hands the statistics to the reporter if it is due
+    fn report(&mut self) {
+        if let Some((interval, ref mut cb)) = self.reporter {
+            if self.time_now - self.last_report >= interval {
+                self.last_report = self.time_now;
+                cb(&self.stats);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+    use std::cell::RefCell;
+    use glium::backend::glutin_backend::GlutinFacade;
+    use glium::glutin::{Event, ElementState, HeadlessRendererBuilder};
+    use glium::DisplayBuild;
+
+    use clock::{Clock, SimulatedClock};
+    use event::{Keys, KeyState, KeyCode, InputEvent, TimedEvent, PhysicalKey};
+    use event::record::Recording;
+    use scene::{Scene, SceneTransition, StackSceneManager};
+    use traits::HasId;
+
+    type State = Rc<RefCell<Vec<f64>>>;
+
+    struct CountingScene;
+
+    impl HasId for CountingScene {
+        fn get_id(&self) -> usize {
+            0
+        }
+    }
+
+    impl Scene for CountingScene {
+        type State = State;
+        fn tick(&mut self, data: &mut State, dt: f64) -> SceneTransition<State> {
+            data.borrow_mut().push(dt);
+            if data.borrow().len() == 120 {
+                SceneTransition::Pop
+            } else {
+                SceneTransition::Nothing
+            }
+        }
+    }
+
+    #[test]
+    fn simulated_clock_ticks() {
+        let clock = SimulatedClock::new();
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::with_clock(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(CountingScene)),
+            create_display(),
+            Rc::new(clock.clone())
+        );
+        let stats = game.stats();
+        game.kickoff();
+
+        assert_eq!(stats.tps(), 60);
+        assert_eq!(stats.update_timing().max, 0.0);
+        assert_eq!(state.borrow().len(), 120);
+        for dt in state.borrow().iter() {
+            assert_eq!(*dt, 0.016666667);
+        }
+        assert_eq!(clock.now_ns(), 120 * 16_666_667);
+    }
+
+    struct KeyScene;
+
+    impl HasId for KeyScene {
+        fn get_id(&self) -> usize {
+            1
+        }
+    }
+
+    impl Scene for KeyScene {
+        type State = State;
+        fn keypress(&mut self, data: &mut State, keys: &Keys) {
+            if let KeyState::Held(t) = keys.status(KeyCode::A) {
+                data.borrow_mut().push(t);
+            }
+        }
+        fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State> {
+            SceneTransition::Nothing
+        }
+    }
+
+    struct EventScene;
+
+    impl HasId for EventScene {
+        fn get_id(&self) -> usize {
+            2
+        }
+    }
+
+    impl Scene for EventScene {
+        type State = State;
+        fn events(&mut self, data: &mut State, events: &[TimedEvent]) {
+            for ev in events {
+                if let InputEvent::Window(Event::KeyboardInput(ElementState::Pressed, _, _)) = ev.event {
+                    data.borrow_mut().push(ev.time);
+                }
+            }
+        }
+        fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State> {
+            SceneTransition::Nothing
+        }
+    }
+
+    fn create_display() -> GlutinFacade {
+        HeadlessRendererBuilder::new(1024, 768).build_glium().unwrap()
+    }
+
+    #[test]
+    fn headless_scripted_keys() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(KeyScene)),
+            create_display()
+        );
+
+        let script = Script::new()
+            .press(2, KeyCode::A)
+            .release(5, KeyCode::A);
+        let run = game.run_headless(10, script);
+
+        assert_eq!(run.ticks, 10);
+        assert_eq!(run.scene_mgr.get_scenes().len(), 1);
+        assert_eq!(*run.state.borrow(), vec![0.050000001, 0.050000001]);
+    }
+
+    #[test]
+    fn script_scancodes() {
+        let mut script = Script::new()
+            .press(0, KeyCode::W)
+            .press(0, KeyCode::Numpad5);
+        let keys: Vec<Option<PhysicalKey>> = script.take(0).into_iter().map(|ev| match ev {
+            Event::KeyboardInput(_, scancode, _) => PhysicalKey::from_scancode(scancode),
+            _ => None
+        }).collect();
+
+        // The keypad has no physical key, so it must not pass for one
+        assert_eq!(keys, vec![Some(PhysicalKey::W), None]);
+    }
+
+    #[test]
+    fn replay_recorded_session() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let mut game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(KeyScene)),
+            create_display()
+        );
+        let recording = game.record();
+        let script = Script::new()
+            .press(2, KeyCode::A)
+            .release(5, KeyCode::A)
+            .type_text(6, "ok");
+        let recorded = game.run_headless(10, script);
+        assert_eq!(recording.len(), 10);
+
+        let mut data = Vec::new();
+        recording.write_to(&mut data).unwrap();
+        let loaded = Recording::read_from(&mut &data[..]).unwrap();
+
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(KeyScene)),
+            create_display()
+        );
+        let replayed = game.replay(&loaded);
+
+        assert_eq!(replayed.ticks, 10);
+        assert_eq!(*replayed.state.borrow(), *recorded.state.borrow());
+        assert_eq!(*replayed.state.borrow(), vec![0.050000001, 0.050000001]);
+    }
+
+    struct DoubleTapScene;
+
+    impl HasId for DoubleTapScene {
+        fn get_id(&self) -> usize {
+            3
+        }
+    }
+
+    impl Scene for DoubleTapScene {
+        type State = State;
+        fn keypress(&mut self, data: &mut State, keys: &Keys) {
+            if keys.double_tapped(KeyCode::A, 0.1) {
+                if let KeyState::Pressed(t) = keys.status(KeyCode::A) {
+                    data.borrow_mut().push(t);
+                }
+            }
+        }
+        fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State> {
+            SceneTransition::Nothing
+        }
+    }
+
+    #[test]
+    fn replay_double_tap() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let mut game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(DoubleTapScene)),
+            create_display()
+        );
+        let recording = game.record();
+        let script = Script::new()
+            .press(2, KeyCode::A)
+            .release(3, KeyCode::A)
+            .press(5, KeyCode::A);
+        let recorded = game.run_headless(10, script);
+        assert_eq!(recorded.state.borrow().len(), 1);
+
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(DoubleTapScene)),
+            create_display()
+        );
+        let replayed = game.replay(&recording);
+
+        assert_eq!(*replayed.state.borrow(), *recorded.state.borrow());
+        // Every frame has the time its tick stamped the input with
+        for (i, frame) in recording.frames().iter().enumerate() {
+            assert_eq!(frame.time, (i as u64 + 1) * 16_666_667);
+        }
+    }
+
+    #[test]
+    fn headless_event_queue() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(EventScene)),
+            create_display()
+        );
+
+        let script = Script::new()
+            .press(2, KeyCode::A)
+            .release(2, KeyCode::A)
+            .press(2, KeyCode::A)
+            .type_text(3, "a");
+        let run = game.run_headless(5, script);
+
+        assert_eq!(*run.state.borrow(), vec![0.050000001, 0.050000001]);
+    }
+
+    #[test]
+    fn headless_background_pause() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let mut game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(CountingScene)),
+            create_display()
+        );
+        game.set_background_policy(BackgroundPolicy::Pause { render_interval: 1.0 });
+
+        let script = Script::new()
+            .at(2, Event::Focused(false))
+            .at(5, Event::Focused(true))
+            .at(6, Event::Resized(0, 0))
+            .at(8, Event::Resized(800, 600));
+        game.run_headless(10, script);
+
+        assert_eq!(state.borrow().len(), 5);
+    }
+
+    #[test]
+    fn headless_stops_without_scenes() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(CountingScene)),
+            create_display()
+        );
+
+        let run = game.run_headless(1000, Script::new());
+
+        assert_eq!(run.ticks, 120);
+        assert_eq!(run.scene_mgr.get_scenes().len(), 0);
+    }
+
+    #[test]
+    fn headless_scripted_close() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(KeyScene)),
+            create_display()
+        );
+
+        let run = game.run_headless(10, Script::new().close(3));
+
+        assert_eq!(run.ticks, 4);
+    }
+
+    struct VetoScene;
+
+    impl HasId for VetoScene {
+        fn get_id(&self) -> usize {
+            2
+        }
+    }
+
+    impl Scene for VetoScene {
+        type State = State;
+        fn close_requested(&mut self, data: &mut State) -> bool {
+            data.borrow_mut().push(-1.0);
+            false
+        }
+        fn leave(&mut self, data: &mut State) {
+            data.borrow_mut().push(-2.0);
+        }
+        fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State> {
+            SceneTransition::Nothing
+        }
+    }
+
+    #[test]
+    fn headless_vetoed_close() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let mut game = Game::new(
+            state.clone(),
+            StackSceneManager::with_scene(state.clone(), Box::new(VetoScene)),
+            create_display()
+        );
+        game.on_shutdown(Box::new(|data: &mut State| data.borrow_mut().push(-3.0)));
+
+        let run = game.run_headless(10, Script::new().close(3));
+
+        assert_eq!(run.ticks, 10);
+        assert_eq!(run.scene_mgr.get_scenes().len(), 0);
+        assert_eq!(*run.state.borrow(), vec![-1.0, -2.0, -3.0]);
+    }
+
 }
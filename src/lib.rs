@@ -28,4 +28,17 @@ pub mod traits;
 /// TODO: Expand
 pub mod game;
 
-pub use game::Game;
+/// Creating a game together with its window
+pub mod builder;
+
+/// Sources of time and time control for the game loop
+pub mod clock;
+
+/// Frame timing statistics
+pub mod stats;
+
+/// Countdowns and repeating timers for scenes
+pub mod timer;
+
+pub use game::{Game, LoopMode};
+pub use builder::GameBuilder;
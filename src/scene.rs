@@ -1,6 +1,8 @@
+use std::path::Path;
 use glium::backend::glutin_backend::GlutinFacade;
-use event::Keys;
+use event::{Input, Keys, Mouse, Gamepads, Actions, TimedEvent};
 use traits::HasId;
+use timer::Timers;
 
 /// Signalling Enum, meant to tell the SceneManager what should happen next.
 pub enum SceneTransition<T : Sized> {
@@ -12,10 +14,33 @@ pub enum SceneTransition<T : Sized> {
     /// `Pop` will remove the current Scene from the stack returning to the previous
     /// one.
     Pop,
+    /// `Replace` will leave and remove the current scene and put the new one
+    /// in its place, in one go. Unlike a `Pop` followed by a `Push` the
+    /// stack never gets empty in between, so this is how to get from a title
+    /// screen to the game.
+    Replace(Box<Scene<State=T>>),
     /// `PopUntil` will remove scenes until the given scene is found, this is useful
     /// to get back to a parent menu for example.
     /// **This panics if the menu does not exist!**
-    PopUntil(usize)
+    PopUntil(usize),
+    /// `Quit` will leave and remove all scenes, top first, which ends the
+    /// game.
+    Quit,
+    /// `ClearAndPush` will leave and remove all scenes like `Quit` does and
+    /// put the new scene on the empty stack, as a fresh root.
+    ClearAndPush(Box<Scene<State=T>>),
+    /// `PushMany` will put all given scenes on the stack in order, the last
+    /// one ending up on top. Only that one is entered, which builds a stack
+    /// of breadcrumbs to go back through.
+    PushMany(Vec<Box<Scene<State=T>>>),
+    /// `Insert` will put the new scene directly below the current one, so it
+    /// is revealed by the next `Pop`. On an empty stack this is a `Push`.
+    Insert(Box<Scene<State=T>>),
+    /// `Remove` will remove the topmost scene with the given id, wherever it
+    /// is on the stack. If it was the top it is left and the scene below is
+    /// entered, covered scenes are simply dropped like with `PopUntil`.
+    /// Nothing happens if there is no such scene.
+    Remove(usize)
 }
 
 /// One of the most important traits for a game, the scene is what tells the
@@ -26,13 +51,64 @@ pub trait Scene : HasId {
     /// Called everytime this scene becomes the top of the stack
     fn enter(&mut self, _state: &mut Self::State) {}
     /// Called everytime this scene stops being the top of the stack (also
-    /// before a drop)
+    /// before it is dropped while being the top)
     fn leave(&mut self, _state: &mut Self::State) {}
+    /// Called on the top scene when the user wants to close the window.
+    /// Return `false` to keep the game running, for example to ask about
+    /// unsaved progress first. Per default the close is accepted.
+    fn close_requested(&mut self, _state: &mut Self::State) -> bool {
+        true
+    }
+    /// Gets all events received since the last tick in the order they
+    /// arrived, for scenes that rather react to events than look at the state
+    /// of the input. This is called before `keypress` and _before_ `tick`.
+    fn events(&mut self, _state: &mut Self::State, _events: &[TimedEvent]) {}
     /// Convenience method where you can handle keyboard input specifically.
     /// This is called _before_ `tick`.
     fn keypress(&mut self, _state: &mut Self::State, _keys: &Keys) {}
-    /// Called with a display to draw into something
-    fn display(&mut self, _state: &mut Self::State, _display: &GlutinFacade) {}
+    /// Convenience method where you can handle mouse input specifically.
+    /// This is called after `keypress` and _before_ `tick`.
+    fn mouse(&mut self, _state: &mut Self::State, _mouse: &Mouse) {}
+    /// Convenience method where you can handle gamepad input specifically.
+    /// This is called after `mouse` and _before_ `tick`.
+    fn gamepad(&mut self, _state: &mut Self::State, _gamepads: &Gamepads) {}
+    /// Convenience method where you can handle named actions, see
+    /// `Game::actions`. This is called after `gamepad` and _before_ `tick`.
+    fn actions(&mut self, _state: &mut Self::State, _actions: &Actions) {}
+    /// Whether this scene wants the input the scenes above it did not
+    /// consume while it is covered, see `Keys::consume`. Per default only
+    /// the top scene gets input.
+    ///
+    /// Input is passed down the stack until it reaches a scene that does not
+    /// want it, so a gameplay scene below a debug overlay can opt in, while a
+    /// pause menu in between still shields it. Covered scenes only get
+    /// `keypress`, `mouse`, `gamepad` and `actions`, after the top scene,
+    /// `events` stay with the top scene. Consumed keys, buttons, text and
+    /// the actions bound to them don't reach the covered scenes.
+    fn receives_covered_input(&self) -> bool {
+        false
+    }
+    /// Called on every scene, from the bottom of the stack to the top, when
+    /// the window has been resized. Covered scenes get it too, so they are
+    /// laid out correctly once they are revealed again.
+    fn resized(&mut self, _state: &mut Self::State, _width: u32, _height: u32) {}
+    /// Called on the top scene when the window gains or loses focus
+    fn focused(&mut self, _state: &mut Self::State, _focused: bool) {}
+    /// Called on the top scene when the window is minimized or restored
+    fn minimized(&mut self, _state: &mut Self::State, _minimized: bool) {}
+    /// Called on the top scene when a file has been dropped onto the window
+    fn file_dropped(&mut self, _state: &mut Self::State, _path: &Path) {}
+    /// Called with a display to draw into something. `alpha` is in `[0, 1)`
+    /// and tells how far the game already is towards the next `tick`, so
+    /// movement can be interpolated.
+    fn display(&mut self, _state: &mut Self::State, _display: &GlutinFacade,
+               _alpha: f64) {}
+    /// Gives the scene manager access to the timers of this scene, they are
+    /// advanced by `dt` before `keypress` and `tick` are called, but only
+    /// while this scene is the top of the stack.
+    fn timers(&mut self) -> Option<&mut Timers<Self::State>> {
+        None
+    }
     /// Called to update the state so as to reflect one advancement in time.
     fn tick(&mut self, _state: &mut Self::State, _dt: f64) -> SceneTransition<Self::State>
     {
@@ -55,9 +131,24 @@ pub trait SceneManager<T : Sized> {
     /// Make the manager handle a given transition.
     fn handle_transition(&mut self, Self::SceneTransition);
     /// Update the scene/s
-    fn update(&mut self, dt: f64, keys: &Keys);
-    /// Display the scene/s
-    fn display(&mut self, display: &GlutinFacade);
+    fn update(&mut self, dt: f64, input: &Input);
+    /// Asks the scene/s whether the game may be closed, per default it may
+    fn close_requested(&mut self) -> bool {
+        true
+    }
+    /// Called once the game ends, so that the remaining scenes can be left
+    fn shutdown(&mut self) {}
+    /// Tells the scene/s that the window has been resized
+    fn resized(&mut self, _width: u32, _height: u32) {}
+    /// Tells the scene/s that the window gained or lost focus
+    fn focused(&mut self, _focused: bool) {}
+    /// Tells the scene/s that the window has been minimized or restored
+    fn minimized(&mut self, _minimized: bool) {}
+    /// Tells the scene/s that a file has been dropped onto the window
+    fn file_dropped(&mut self, _path: &Path) {}
+    /// Display the scene/s, `alpha` is the interpolation between the last and
+    /// the next update
+    fn display(&mut self, display: &GlutinFacade, alpha: f64);
 }
 
 /// A sample implementation of `SceneManager` can be used as is for a stack
@@ -114,6 +205,15 @@ impl<T> SceneManager<T> for StackSceneManager<T> where T: Sized + Clone {
                     s.leave(&mut self.state);
                 }
             },
+            Replace(boxed_scene) => {
+                if let Some(mut s) = self.scenes.pop() {
+                    s.leave(&mut self.state);
+                }
+                self.scenes.push(boxed_scene);
+                if let Some(s) = self.scenes.last_mut() {
+                    s.enter(&mut self.state);
+                }
+            },
             PopUntil(id) => {
                 // If we have just one or zero scenes we can simply panic.
                 // If not then we just call leave once and iterate through
@@ -152,23 +252,124 @@ impl<T> SceneManager<T> for StackSceneManager<T> where T: Sized + Clone {
                         s.enter(&mut self.state);
                     }
                 }
+            },
+            Quit => {
+                while let Some(mut s) = self.scenes.pop() {
+                    s.leave(&mut self.state);
+                }
+            },
+            ClearAndPush(boxed_scene) => {
+                self.handle_transition(Quit);
+                self.handle_transition(Push(boxed_scene));
+            },
+            PushMany(boxed_scenes) => {
+                if boxed_scenes.is_empty() {
+                    return;
+                }
+                if let Some(s) = self.scenes.last_mut() {
+                    s.leave(&mut self.state);
+                }
+                self.scenes.extend(boxed_scenes);
+                if let Some(s) = self.scenes.last_mut() {
+                    s.enter(&mut self.state);
+                }
+            },
+            Insert(boxed_scene) => {
+                if self.scenes.is_empty() {
+                    self.handle_transition(Push(boxed_scene));
+                } else {
+                    let below = self.scenes.len() - 1;
+                    self.scenes.insert(below, boxed_scene);
+                }
+            },
+            Remove(id) => {
+                let index = self.scenes.iter().rposition(|s| s.get_id() == id);
+                if let Some(index) = index {
+                    if index + 1 == self.scenes.len() {
+                        self.handle_transition(Pop);
+                        if let Some(s) = self.scenes.last_mut() {
+                            s.enter(&mut self.state);
+                        }
+                    } else {
+                        // Covered scenes have already been left
+                        self.scenes.remove(index);
+                    }
+                }
             }
         }
     }
 
-    fn update(&mut self, dt: f64, keys: &Keys) {
+    fn close_requested(&mut self) -> bool {
+        match self.scenes.last_mut() {
+            Some(s) => s.close_requested(&mut self.state),
+            None => true
+        }
+    }
+
+    fn shutdown(&mut self) {
+        self.handle_transition(SceneTransition::Quit);
+    }
+
+    fn resized(&mut self, width: u32, height: u32) {
+        for s in self.scenes.iter_mut() {
+            s.resized(&mut self.state, width, height);
+        }
+    }
+
+    fn focused(&mut self, focused: bool) {
+        if let Some(s) = self.scenes.last_mut() {
+            s.focused(&mut self.state, focused);
+        }
+    }
+
+    fn minimized(&mut self, minimized: bool) {
+        if let Some(s) = self.scenes.last_mut() {
+            s.minimized(&mut self.state, minimized);
+        }
+    }
+
+    fn file_dropped(&mut self, path: &Path) {
+        if let Some(s) = self.scenes.last_mut() {
+            s.file_dropped(&mut self.state, path);
+        }
+    }
+
+    fn update(&mut self, dt: f64, input: &Input) {
         let mut state = self.state.clone();
+        if let Some(timers) = self.get_scenes_mut().last_mut().unwrap().timers() {
+            timers.advance(dt, &mut state);
+        }
+        self.get_scenes_mut().last_mut()
+            .unwrap().events(&mut state, &input.events);
+        self.get_scenes_mut().last_mut()
+            .unwrap().keypress(&mut state, &input.keys);
         self.get_scenes_mut().last_mut()
-            .unwrap().keypress(&mut state, keys);
+            .unwrap().mouse(&mut state, &input.mouse);
+        self.get_scenes_mut().last_mut()
+            .unwrap().gamepad(&mut state, &input.gamepads);
+        input.actions.consume_inputs(&input.keys, &input.mouse, &input.gamepads);
+        self.get_scenes_mut().last_mut()
+            .unwrap().actions(&mut state, &input.actions);
+        // Pass on what is left of the input to the covered scenes that want it
+        for s in self.scenes.iter_mut().rev().skip(1) {
+            if !s.receives_covered_input() {
+                break;
+            }
+            s.keypress(&mut state, &input.keys);
+            s.mouse(&mut state, &input.mouse);
+            s.gamepad(&mut state, &input.gamepads);
+            input.actions.consume_inputs(&input.keys, &input.mouse, &input.gamepads);
+            s.actions(&mut state, &input.actions);
+        }
         let answer = self.get_scenes_mut().last_mut()
             .unwrap().tick(&mut state, dt);
         self.handle_transition(answer);
     }
 
-    fn display(&mut self, display: &GlutinFacade) {
+    fn display(&mut self, display: &GlutinFacade, alpha: f64) {
         let mut state = self.state.clone();
         self.get_scenes_mut().last_mut()
-            .unwrap().display(&mut state, display);
+            .unwrap().display(&mut state, display, alpha);
     }
 }
 
@@ -182,11 +383,15 @@ mod test {
     use glium::DisplayBuild;
 
     use traits::HasId;
+    use timer::Timers;
+    use event::Input;
 
     struct TestData {
         has_been_modified: usize,
         has_entered:       usize,
         has_left:          usize,
+        /// What happened to the scenes, in order
+        log:               Vec<String>,
     }
 
     type State = Rc<RefCell<TestData>>;
@@ -196,6 +401,7 @@ mod test {
             has_been_modified: 0,
             has_entered:       0,
             has_left:          0,
+            log:               Vec::new(),
         }))
     }
 
@@ -271,7 +477,8 @@ mod test {
 
         impl Scene for TestScene {
             type State = State;
-            fn display(&mut self, data: &mut Self::State, display: &GlutinFacade) {
+            fn display(&mut self, data: &mut Self::State, display: &GlutinFacade,
+                       _alpha: f64) {
                 use glium::Surface;
                 let mut frame = display.draw();
                 frame.clear_color(0.,1.,0.,1.0);
@@ -284,7 +491,7 @@ mod test {
 
         let mut scene = TestScene;
 
-        scene.display(&mut state, &display);
+        scene.display(&mut state, &display, 0.0);
 
         assert_eq!(state.borrow().has_been_modified, 1);
     }
@@ -386,4 +593,318 @@ mod test {
 
     }
 
+    #[test]
+    fn replace_manager() {
+        struct TestScene(usize);
+
+        impl HasId for TestScene {
+            fn get_id(&self) -> usize {
+                self.0
+            }
+        }
+
+        impl Scene for TestScene {
+            type State = State;
+            fn enter(&mut self, data: &mut State) {
+                data.borrow_mut().has_entered += 1;
+            }
+            fn leave(&mut self, data: &mut State) {
+                data.borrow_mut().has_left += 1;
+            }
+            fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State>
+            {
+                SceneTransition::Replace(Box::new(TestScene(self.0 + 1)))
+            }
+        }
+
+        let state = create_state();
+        let mut mgr = create_scene_manager(state.clone());
+
+        mgr.handle_transition(SceneTransition::Push(Box::new(TestScene(0))));
+        mgr.update(0.0, &Input::new());
+
+        assert_eq!(mgr.get_scenes().len(), 1);
+        assert_eq!(mgr.get_scenes()[0].get_id(), 1);
+        assert_eq!(state.borrow().has_entered, 2);
+        assert_eq!(state.borrow().has_left, 1);
+
+        // Replacing on an empty stack simply pushes
+        let mut empty = create_scene_manager(state.clone());
+        empty.handle_transition(SceneTransition::Replace(Box::new(TestScene(5))));
+        assert_eq!(empty.get_scenes().len(), 1);
+        assert_eq!(state.borrow().has_entered, 3);
+    }
+
+    #[test]
+    fn timers_freeze_when_covered() {
+        struct TimedScene {
+            timers: Timers<State>
+        }
+
+        impl HasId for TimedScene {
+            fn get_id(&self) -> usize {
+                0
+            }
+        }
+
+        impl Scene for TimedScene {
+            type State = State;
+            fn timers(&mut self) -> Option<&mut Timers<State>> {
+                Some(&mut self.timers)
+            }
+            fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State>
+            {
+                SceneTransition::Nothing
+            }
+        }
+
+        struct CoverScene;
+
+        impl HasId for CoverScene {
+            fn get_id(&self) -> usize {
+                1
+            }
+        }
+
+        impl Scene for CoverScene {
+            type State = State;
+            fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State>
+            {
+                SceneTransition::Nothing
+            }
+        }
+
+        let state = create_state();
+        let mut mgr = create_scene_manager(state.clone());
+        let input = Input::new();
+
+        let mut timers = Timers::new();
+        timers.every(1.0, Box::new(|data: &mut State| {
+            data.borrow_mut().has_been_modified += 1;
+        }));
+        mgr.handle_transition(SceneTransition::Push(Box::new(TimedScene { timers: timers })));
+
+        mgr.update(1.0, &input);
+        assert_eq!(state.borrow().has_been_modified, 1);
+
+        mgr.handle_transition(SceneTransition::Push(Box::new(CoverScene)));
+        mgr.update(1.0, &input);
+        assert_eq!(state.borrow().has_been_modified, 1);
+
+        mgr.handle_transition(SceneTransition::Pop);
+        mgr.update(1.0, &input);
+        assert_eq!(state.borrow().has_been_modified, 2);
+    }
+
+    #[test]
+    fn covered_input() {
+        use glium::glutin::ElementState::{Pressed, Released};
+        use event::{Keys, KeyCode, Actions, ActionMap, Binding};
+
+        /// Takes F1 and the typed text for itself
+        struct Overlay;
+
+        impl HasId for Overlay {
+            fn get_id(&self) -> usize {
+                2
+            }
+        }
+
+        impl Scene for Overlay {
+            type State = State;
+            fn keypress(&mut self, data: &mut State, keys: &Keys) {
+                if keys.pressed(KeyCode::F1) {
+                    keys.consume(KeyCode::F1);
+                    data.borrow_mut().log.push("overlay F1".to_string());
+                }
+                keys.text().consume();
+            }
+            fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State>
+            {
+                SceneTransition::Nothing
+            }
+        }
+
+        /// Logs the F1 and Space presses, text and actions it gets, opts in
+        /// to covered input if its flag is set
+        struct Gameplay(bool);
+
+        impl HasId for Gameplay {
+            fn get_id(&self) -> usize {
+                self.0 as usize
+            }
+        }
+
+        impl Scene for Gameplay {
+            type State = State;
+            fn receives_covered_input(&self) -> bool {
+                self.0
+            }
+            fn keypress(&mut self, data: &mut State, keys: &Keys) {
+                if keys.pressed(KeyCode::F1) {
+                    data.borrow_mut().log.push("gameplay F1".to_string());
+                }
+                if keys.pressed(KeyCode::Space) {
+                    data.borrow_mut().log.push("gameplay Space".to_string());
+                }
+                if !keys.text().typed().is_empty() {
+                    data.borrow_mut().log.push("gameplay text".to_string());
+                }
+            }
+            fn actions(&mut self, data: &mut State, actions: &Actions) {
+                for action in &["debug", "jump"] {
+                    if actions.pressed(action) {
+                        data.borrow_mut().log.push(format!("gameplay {}", action));
+                    }
+                }
+            }
+        }
+
+        let state = create_state();
+        let mut mgr = create_scene_manager(state.clone());
+        mgr.handle_transition(SceneTransition::Push(Box::new(Gameplay(true))));
+        mgr.handle_transition(SceneTransition::Push(Box::new(Overlay)));
+
+        let map = ActionMap::new();
+        map.bind("debug", Binding::Key(KeyCode::F1));
+        map.bind("jump", Binding::Key(KeyCode::Space));
+        let mut input = Input::new();
+        input.actions = Actions::new(map);
+        input.keys.text().start();
+        input.keys.update(1.0);
+        input.keys.update_key(KeyCode::F1, Pressed, 1.0);
+        input.keys.update_key(KeyCode::Space, Pressed, 1.0);
+        input.keys.update_char('a');
+        input.update_actions(1.0);
+        mgr.update(0.0, &input);
+        assert_eq!(state.borrow().log,
+                   vec!["overlay F1", "gameplay Space", "gameplay jump"]);
+        assert!(input.actions.is_consumed("debug"));
+
+        // The consumption only lasts for a tick
+        assert!(input.keys.is_consumed(KeyCode::F1));
+        input.keys.update(2.0);
+        assert!(!input.keys.is_consumed(KeyCode::F1));
+        input.keys.update_key(KeyCode::Space, Released, 2.0);
+
+        // A scene in between that does not opt in shields the gameplay
+        mgr.handle_transition(SceneTransition::Pop);
+        mgr.handle_transition(SceneTransition::Push(Box::new(Gameplay(false))));
+        mgr.handle_transition(SceneTransition::Push(Box::new(Overlay)));
+        input.keys.update(3.0);
+        input.keys.update_key(KeyCode::Space, Pressed, 3.0);
+        state.borrow_mut().log.clear();
+        mgr.update(0.0, &input);
+        assert!(state.borrow().log.is_empty());
+    }
+
+    #[test]
+    fn stack_operations() {
+        struct TestScene(usize);
+
+        impl HasId for TestScene {
+            fn get_id(&self) -> usize {
+                self.0
+            }
+        }
+
+        impl Scene for TestScene {
+            type State = State;
+            fn enter(&mut self, data: &mut State) {
+                data.borrow_mut().log.push(format!("enter {}", self.0));
+            }
+            fn leave(&mut self, data: &mut State) {
+                data.borrow_mut().log.push(format!("leave {}", self.0));
+            }
+        }
+
+        fn ids(mgr: &StackSceneManager<State>) -> Vec<usize> {
+            mgr.get_scenes().iter().map(|s| s.get_id()).collect()
+        }
+
+        let state = create_state();
+        let mut mgr = create_scene_manager(state.clone());
+
+        // Only the new top is entered
+        mgr.handle_transition(SceneTransition::PushMany(vec![
+            Box::new(TestScene(1)), Box::new(TestScene(2)), Box::new(TestScene(3))
+        ]));
+        assert_eq!(ids(&mgr), vec![1, 2, 3]);
+        assert_eq!(state.borrow().log, vec!["enter 3"]);
+
+        mgr.handle_transition(SceneTransition::Insert(Box::new(TestScene(4))));
+        assert_eq!(ids(&mgr), vec![1, 2, 4, 3]);
+        assert_eq!(state.borrow().log, vec!["enter 3"]);
+
+        // A covered scene is not entered, so it is dropped without a leave
+        mgr.handle_transition(SceneTransition::Remove(2));
+        assert_eq!(ids(&mgr), vec![1, 4, 3]);
+        assert_eq!(state.borrow().log, vec!["enter 3"]);
+
+        mgr.handle_transition(SceneTransition::Remove(3));
+        assert_eq!(ids(&mgr), vec![1, 4]);
+        assert_eq!(state.borrow().log, vec!["enter 3", "leave 3", "enter 4"]);
+
+        mgr.handle_transition(SceneTransition::Remove(7));
+        assert_eq!(ids(&mgr), vec![1, 4]);
+
+        mgr.handle_transition(SceneTransition::ClearAndPush(Box::new(TestScene(5))));
+        assert_eq!(ids(&mgr), vec![5]);
+        assert_eq!(state.borrow().log, vec!["enter 3", "leave 3", "enter 4",
+                                            "leave 4", "leave 1", "enter 5"]);
+
+        let mut empty = create_scene_manager(state.clone());
+        empty.handle_transition(SceneTransition::Insert(Box::new(TestScene(6))));
+        assert_eq!(ids(&empty), vec![6]);
+        assert_eq!(state.borrow().log.last().map(|s| &s[..]), Some("enter 6"));
+    }
+
+    #[test]
+    fn quit_manager() {
+        struct TestScene(usize);
+
+        impl HasId for TestScene {
+            fn get_id(&self) -> usize {
+                self.0
+            }
+        }
+
+        impl Scene for TestScene {
+            type State = State;
+            fn leave(&mut self, data: &mut State) {
+                data.borrow_mut().log.push(format!("leave {}", self.0));
+            }
+            fn close_requested(&mut self, _data: &mut State) -> bool {
+                self.0 != 2
+            }
+            fn tick(&mut self, _data: &mut State, _dt: f64) -> SceneTransition<State>
+            {
+                SceneTransition::Quit
+            }
+        }
+
+        let state = create_state();
+        let mut mgr = create_scene_manager(state.clone());
+
+        mgr.handle_transition(SceneTransition::Push(Box::new(TestScene(1))));
+        mgr.handle_transition(SceneTransition::Push(Box::new(TestScene(2))));
+        assert!(!mgr.close_requested());
+        mgr.handle_transition(SceneTransition::Push(Box::new(TestScene(3))));
+        assert!(mgr.close_requested());
+
+        state.borrow_mut().log.clear();
+        mgr.update(0.0, &Input::new());
+
+        assert_eq!(mgr.get_scenes().len(), 0);
+        assert_eq!(state.borrow().log, vec!["leave 3", "leave 2", "leave 1"]);
+
+        // Shutting down leaves every remaining scene, top first
+        mgr.handle_transition(SceneTransition::Push(Box::new(TestScene(1))));
+        mgr.handle_transition(SceneTransition::Push(Box::new(TestScene(2))));
+        state.borrow_mut().log.clear();
+        mgr.shutdown();
+        assert_eq!(mgr.get_scenes().len(), 0);
+        assert_eq!(state.borrow().log, vec!["leave 2", "leave 1"]);
+    }
+
 }
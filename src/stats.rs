@@ -0,0 +1,242 @@
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// How many duration samples are kept for each phase
+pub const DEFAULT_WINDOW: usize = 240;
+
+/// A summary of how long a phase of the game loop took, all values are in
+/// seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Timing {
+    /// The fastest sample
+    pub min: f64,
+    /// The mean of all samples
+    pub avg: f64,
+    /// The slowest sample
+    pub max: f64,
+    /// 99% of the samples were at most this slow
+    pub p99: f64,
+}
+
+impl Timing {
+    fn from_samples(samples: &VecDeque<f64>) -> Timing {
+        if samples.is_empty() {
+            return Timing { min: 0.0, avg: 0.0, max: 0.0, p99: 0.0 };
+        }
+
+        let mut sorted: Vec<f64> = samples.iter().cloned().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let len = sorted.len();
+        let sum = sorted.iter().fold(0.0, |acc, s| acc + s);
+        let p99 = cmp::max((len as f64 * 0.99).ceil() as usize, 1) - 1;
+
+        Timing {
+            min: sorted[0],
+            avg: sum / len as f64,
+            max: sorted[len - 1],
+            p99: sorted[p99],
+        }
+    }
+}
+
+struct StatsData {
+    window: usize,
+    started: f64,
+    now: f64,
+    ticks: VecDeque<f64>,
+    frames: VecDeque<f64>,
+    update: VecDeque<f64>,
+    display: VecDeque<f64>,
+    lags: VecDeque<f64>,
+    lagged_frames: u64,
+    dropped_time: f64,
+}
+
+/// Rolling frame statistics of a running `Game`.
+///
+/// This is a cheap handle, clones all look at the same numbers. Get one with
+/// `Game::stats` and put it into your state if your scenes want to query it.
+#[derive(Clone)]
+pub struct Stats {
+    data: Rc<RefCell<StatsData>>
+}
+
+impl Stats {
+    /// Creates empty statistics keeping the last `window` samples per phase
+    pub fn new(window: usize) -> Stats {
+        Stats {
+            data: Rc::new(RefCell::new(StatsData {
+                window: window,
+                started: 0.0,
+                now: 0.0,
+                ticks: VecDeque::new(),
+                frames: VecDeque::new(),
+                update: VecDeque::new(),
+                display: VecDeque::new(),
+                lags: VecDeque::new(),
+                lagged_frames: 0,
+                dropped_time: 0.0,
+            }))
+        }
+    }
+
+    /// Ticks (calls to `SceneManager::update`) during the last second
+    pub fn tps(&self) -> usize {
+        self.data.borrow().ticks.len()
+    }
+
+    /// Frames (calls to `SceneManager::display`) during the last second
+    pub fn fps(&self) -> usize {
+        self.data.borrow().frames.len()
+    }
+
+    /// How long `SceneManager::update` took
+    pub fn update_timing(&self) -> Timing {
+        Timing::from_samples(&self.data.borrow().update)
+    }
+
+    /// How long `SceneManager::display` took
+    pub fn display_timing(&self) -> Timing {
+        Timing::from_samples(&self.data.borrow().display)
+    }
+
+    /// How many frames the simulation fell behind since the game started,
+    /// see `event::step::CatchUp`
+    pub fn lagged_frames(&self) -> u64 {
+        self.data.borrow().lagged_frames
+    }
+
+    /// How many frames the simulation fell behind during the last second,
+    /// anything above 0 means the game is running slow.
+    pub fn lagged_last_second(&self) -> usize {
+        self.data.borrow().lags.len()
+    }
+
+    /// How many seconds of game time were dropped in total because the
+    /// simulation fell behind
+    pub fn dropped_time(&self) -> f64 {
+        self.data.borrow().dropped_time
+    }
+
+    /// The time the game was started at
+    pub fn time_started(&self) -> f64 {
+        self.data.borrow().started
+    }
+
+    /// The time of the last recorded tick or frame
+    pub fn time_now(&self) -> f64 {
+        self.data.borrow().now
+    }
+
+    /// Seconds since the game has been started
+    pub fn uptime(&self) -> f64 {
+        let data = self.data.borrow();
+        data.now - data.started
+    }
+
+    /// Marks the start of the game, resetting everything recorded so far
+    pub fn start(&self, time: f64) {
+        let mut data = self.data.borrow_mut();
+        data.started = time;
+        data.now = time;
+        data.ticks.clear();
+        data.frames.clear();
+        data.update.clear();
+        data.display.clear();
+        data.lags.clear();
+        data.lagged_frames = 0;
+        data.dropped_time = 0.0;
+    }
+
+    /// Records an update that started at `time` and took `duration` seconds
+    pub fn record_update(&self, time: f64, duration: f64) {
+        let mut data = self.data.borrow_mut();
+        let data = &mut *data;
+        data.now = time;
+        Stats::push_event(&mut data.ticks, time);
+        Stats::push_sample(&mut data.update, data.window, duration);
+    }
+
+    /// Records a draw that started at `time` and took `duration` seconds
+    pub fn record_display(&self, time: f64, duration: f64) {
+        let mut data = self.data.borrow_mut();
+        let data = &mut *data;
+        data.now = time;
+        Stats::push_event(&mut data.frames, time);
+        Stats::push_sample(&mut data.display, data.window, duration);
+    }
+
+    /// Records that at `time` the simulation fell behind and `dropped`
+    /// seconds were not simulated
+    pub fn record_lag(&self, time: f64, dropped: f64) {
+        let mut data = self.data.borrow_mut();
+        data.lagged_frames += 1;
+        data.dropped_time += dropped;
+        Stats::push_event(&mut data.lags, time);
+    }
+
+    fn push_event(events: &mut VecDeque<f64>, time: f64) {
+        events.push_back(time);
+        while events.front().map_or(false, |&t| t <= time - 1.0) {
+            events.pop_front();
+        }
+    }
+
+    fn push_sample(samples: &mut VecDeque<f64>, window: usize, duration: f64) {
+        samples.push_back(duration);
+        while samples.len() > window {
+            samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rates_only_count_last_second() {
+        let stats = Stats::new(DEFAULT_WINDOW);
+        stats.start(0.0);
+
+        for i in 0..10 {
+            stats.record_update(i as f64 * 0.25, 0.001);
+        }
+        stats.record_display(2.5, 0.002);
+
+        assert_eq!(stats.tps(), 4);
+        assert_eq!(stats.fps(), 1);
+        assert_eq!(stats.uptime(), 2.5);
+    }
+
+    #[test]
+    fn timings() {
+        let stats = Stats::new(100);
+
+        for i in 0..200 {
+            stats.record_update(0.0, i as f64);
+        }
+
+        let timing = stats.update_timing();
+        assert_eq!(timing.min, 100.0);
+        assert_eq!(timing.max, 199.0);
+        assert_eq!(timing.avg, 149.5);
+        assert_eq!(timing.p99, 198.0);
+        assert_eq!(stats.display_timing().max, 0.0);
+    }
+
+    #[test]
+    fn lag() {
+        let stats = Stats::new(DEFAULT_WINDOW);
+
+        stats.record_lag(1.0, 0.5);
+        stats.record_lag(2.5, 0.25);
+
+        assert_eq!(stats.lagged_frames(), 2);
+        assert_eq!(stats.lagged_last_second(), 1);
+        assert_eq!(stats.dropped_time(), 0.75);
+    }
+}
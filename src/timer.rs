@@ -0,0 +1,224 @@
+/// Identifies a scheduled timer, use it to cancel the timer or to check
+/// whether it is the one that fired.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TimerHandle(usize);
+
+/// What happens once a timer runs out
+enum Action<T> {
+    /// Call the closure with the state
+    Callback(Box<FnMut(&mut T)>),
+    /// Only report it through `Timers::fired`
+    Event,
+}
+
+struct Timer<T> {
+    handle: TimerHandle,
+    remaining: f64,
+    interval: Option<f64>,
+    action: Action<T>,
+}
+
+/// A collection of countdowns that get driven by the `dt` of the game loop.
+///
+/// A scene that wants timers keeps a `Timers` around and hands it out through
+/// `Scene::timers`, the scene manager then advances it right before the
+/// scene's `keypress` and `tick`. As only the top scene is advanced, timers
+/// freeze while their scene is covered, and they are dropped together with
+/// the scene.
+pub struct Timers<T> {
+    next: usize,
+    paused: bool,
+    timers: Vec<Timer<T>>,
+    fired: Vec<TimerHandle>,
+}
+
+impl<T> Timers<T> {
+    /// Creates an empty set of timers
+    pub fn new() -> Timers<T> {
+        Timers {
+            next: 0,
+            paused: false,
+            timers: Vec::new(),
+            fired: Vec::new(),
+        }
+    }
+
+    /// Calls `cb` once after `secs` seconds
+    pub fn after(&mut self, secs: f64, cb: Box<FnMut(&mut T)>) -> TimerHandle {
+        self.schedule(secs, None, Action::Callback(cb))
+    }
+
+    /// Calls `cb` every `secs` seconds until cancelled
+    ///
+    /// **This panics if `secs` is not positive!**
+    pub fn every(&mut self, secs: f64, cb: Box<FnMut(&mut T)>) -> TimerHandle {
+        self.schedule(secs, Some(secs), Action::Callback(cb))
+    }
+
+    /// Reports the returned handle in `fired` once after `secs` seconds
+    pub fn event_after(&mut self, secs: f64) -> TimerHandle {
+        self.schedule(secs, None, Action::Event)
+    }
+
+    /// Reports the returned handle in `fired` every `secs` seconds until
+    /// cancelled
+    ///
+    /// **This panics if `secs` is not positive!**
+    pub fn event_every(&mut self, secs: f64) -> TimerHandle {
+        self.schedule(secs, Some(secs), Action::Event)
+    }
+
+    /// Stops a timer, returns whether it was still scheduled
+    pub fn cancel(&mut self, handle: TimerHandle) -> bool {
+        let before = self.timers.len();
+        self.timers.retain(|t| t.handle != handle);
+        before != self.timers.len()
+    }
+
+    /// Stops all timers
+    pub fn clear(&mut self) {
+        self.timers.clear();
+        self.fired.clear();
+    }
+
+    /// Whether the timer is still going to fire
+    pub fn is_scheduled(&self, handle: TimerHandle) -> bool {
+        self.timers.iter().any(|t| t.handle == handle)
+    }
+
+    /// Seconds until the timer fires next
+    pub fn remaining(&self, handle: TimerHandle) -> Option<f64> {
+        self.timers.iter().find(|t| t.handle == handle).map(|t| t.remaining)
+    }
+
+    /// Freezes all timers until `resume` is called
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Lets the timers run again
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Whether the timers are frozen
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// The event timers that fired during the last `advance`, a repeating
+    /// timer is listed once per time it fired.
+    pub fn fired(&self) -> &[TimerHandle] {
+        &self.fired
+    }
+
+    /// Whether the given timer fired during the last `advance`
+    pub fn has_fired(&self, handle: TimerHandle) -> bool {
+        self.fired.contains(&handle)
+    }
+
+    /// Moves all timers `dt` seconds forward, calling the callbacks of those
+    /// that run out with `state`. This is done for you by the scene manager.
+    pub fn advance(&mut self, dt: f64, state: &mut T) {
+        self.fired.clear();
+        if self.paused {
+            return;
+        }
+
+        let mut i = 0;
+        while i < self.timers.len() {
+            let mut done = false;
+            {
+                let timer = &mut self.timers[i];
+                timer.remaining -= dt;
+
+                while timer.remaining <= 0.0 {
+                    match timer.action {
+                        Action::Callback(ref mut cb) => cb(state),
+                        Action::Event => self.fired.push(timer.handle),
+                    }
+
+                    match timer.interval {
+                        Some(interval) => timer.remaining += interval,
+                        None => {
+                            done = true;
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if done {
+                self.timers.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    fn schedule(&mut self, secs: f64, interval: Option<f64>, action: Action<T>)
+        -> TimerHandle
+    {
+        if let Some(interval) = interval {
+            if interval <= 0.0 {
+                panic!("Tried to schedule a repeating timer every {} seconds.", interval);
+            }
+        }
+
+        let handle = TimerHandle(self.next);
+        self.next += 1;
+        self.timers.push(Timer {
+            handle: handle,
+            remaining: secs,
+            interval: interval,
+            action: action,
+        });
+        handle
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn one_shot_and_repeating() {
+        let mut timers = Timers::new();
+        let mut count = 0;
+
+        let once = timers.after(1.0, Box::new(|count: &mut usize| *count += 10));
+        let every = timers.event_every(0.3);
+
+        timers.advance(0.5, &mut count);
+        assert_eq!(count, 0);
+        assert_eq!(timers.fired(), &[every]);
+
+        timers.advance(0.5, &mut count);
+        assert_eq!(count, 10);
+        assert!(!timers.is_scheduled(once));
+
+        timers.advance(1.0, &mut count);
+        assert_eq!(timers.fired(), &[every, every, every]);
+    }
+
+    #[test]
+    fn cancel_and_pause() {
+        let mut timers = Timers::new();
+        let mut count = 0;
+
+        let a = timers.every(0.1, Box::new(|count: &mut usize| *count += 1));
+        let b = timers.event_after(0.1);
+
+        timers.pause();
+        timers.advance(1.0, &mut count);
+        assert_eq!(count, 0);
+        assert_eq!(timers.remaining(b), Some(0.1));
+
+        timers.resume();
+        assert!(timers.cancel(a));
+        assert!(!timers.cancel(a));
+        timers.advance(1.0, &mut count);
+        assert_eq!(count, 0);
+        assert!(timers.has_fired(b));
+    }
+}